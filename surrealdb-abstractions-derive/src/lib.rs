@@ -0,0 +1,355 @@
+//! `#[derive(SurrealTable)]`, see `surrealdb_abstraction::db::SurrealTable`.
+
+use heck::{ToShoutySnakeCase, ToSnakeCase};
+use proc_macro::TokenStream;
+use proc_macro2::Span;
+use quote::{format_ident, quote, quote_spanned};
+use syn::meta::ParseNestedMeta;
+use syn::spanned::Spanned;
+use syn::{parse_macro_input, Attribute, Data, DeriveInput, Fields, Ident, LitStr, Type};
+
+#[proc_macro_derive(SurrealTable, attributes(surreal))]
+pub fn derive_surreal_table(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    match expand(input) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+/// A stored field of the struct.
+struct TableField {
+    ident: Ident,
+    ty: Type,
+    name: String,
+}
+
+fn expand(input: DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    &input.ident,
+                    "SurrealTable can only be derived for structs with named fields",
+                ))
+            }
+        },
+        _ => {
+            return Err(syn::Error::new_spanned(
+                &input.ident,
+                "SurrealTable can only be derived for structs",
+            ))
+        }
+    };
+
+    let table = table_name(&input)?;
+    let rename_all = rename_all(&input.attrs)?;
+
+    let mut stored = Vec::new();
+
+    for field in fields {
+        let ident = field.ident.clone().expect("named fields have identifiers");
+        let options = FieldOptions::parse(&field.attrs)?;
+
+        if options.skip {
+            continue;
+        }
+
+        let name = options.rename.unwrap_or_else(|| {
+            let name = ident.to_string().trim_start_matches("r#").to_string();
+
+            match &rename_all {
+                Some(rule) => rule.apply(&name),
+                None => name,
+            }
+        });
+
+        stored.push(TableField {
+            ident,
+            ty: field.ty.clone(),
+            name,
+        });
+    }
+
+    let krate = quote!(::surrealdb_abstraction);
+    let value = quote!(#krate::__private::surrealdb::sql::Value);
+
+    // Fields are converted one by one from a clone, as `to_field_value` needs them owned. The
+    // clone is spanned to the field, so a missing `Clone` impl is reported on it.
+    let owned = |TableField { ident, ty, .. }: &TableField| quote_spanned!(ty.span()=> <#ty as ::std::clone::Clone>::clone(&self.#ident));
+
+    let (id, content): (Vec<_>, Vec<_>) = stored.iter().partition(|field| field.name == "id");
+
+    let record_id = match id.first() {
+        Some(field) => {
+            let id = owned(field);
+
+            quote! {
+                match #krate::db::to_field_value(#id)? {
+                    #value::None | #value::Null => ::std::result::Result::Ok(::std::option::Option::None),
+                    id => ::std::result::Result::Ok(::std::option::Option::Some(id)),
+                }
+            }
+        }
+        None => quote!(::std::result::Result::Ok(::std::option::Option::None)),
+    };
+
+    let insert = content.iter().map(|field| {
+        let name = &field.name;
+        let field = owned(field);
+
+        quote! {
+            content.insert(
+                ::std::string::ToString::to_string(#name),
+                #krate::db::to_field_value(#field)?,
+            );
+        }
+    });
+
+    let constants = stored.iter().map(|TableField { ident, name, .. }| {
+        let constant = Ident::new(
+            &ident
+                .to_string()
+                .trim_start_matches("r#")
+                .to_shouty_snake_case(),
+            Span::call_site(),
+        );
+        let doc = format!("The `{}` field.", name);
+
+        quote! {
+            #[doc = #doc]
+            pub const #constant: #krate::db::Field = #krate::db::Field::new(#name);
+        }
+    });
+
+    let ident = &input.ident;
+    let vis = &input.vis;
+    let fields = format_ident!("{}Fields", ident);
+    let fields_doc = format!("The stored fields of [`{}`].", ident);
+    let (impl_generics, type_generics, where_clause) = input.generics.split_for_impl();
+    let content_type = quote!(::std::collections::BTreeMap<::std::string::String, #value>);
+
+    Ok(quote! {
+        impl #impl_generics #krate::db::SurrealTable for #ident #type_generics #where_clause {
+            const TABLE: &'static str = #table;
+
+            fn content(&self) -> #krate::Result<#content_type> {
+                let mut content = ::std::collections::BTreeMap::new();
+                #( #insert )*
+
+                ::std::result::Result::Ok(content)
+            }
+
+            fn record_id(&self) -> #krate::Result<::std::option::Option<#value>> {
+                #record_id
+            }
+        }
+
+        #[doc = #fields_doc]
+        #vis struct #fields;
+
+        impl #fields {
+            #( #constants )*
+        }
+    })
+}
+
+/// `#[surreal(table = "...")]`, or the struct name in snake case.
+fn table_name(input: &DeriveInput) -> syn::Result<String> {
+    let mut table = None;
+
+    for attr in input
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("surreal"))
+    {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("table") {
+                table = Some(meta.value()?.parse::<LitStr>()?.value());
+                Ok(())
+            } else {
+                Err(meta.error("expected `table = \"...\"`"))
+            }
+        })?;
+    }
+
+    Ok(table.unwrap_or_else(|| input.ident.to_string().to_snake_case()))
+}
+
+/// `#[serde(rename_all = "...")]` or `#[serde(rename_all(serialize = "..."))]` of the struct.
+fn rename_all(attrs: &[Attribute]) -> syn::Result<Option<RenameRule>> {
+    let mut rule = None;
+
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("serde")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("rename_all") {
+                if let Some(name) = serialize_name(&meta)? {
+                    rule = Some(RenameRule::parse(&name)?);
+                }
+            } else {
+                skip_meta(&meta)?;
+            }
+
+            Ok(())
+        })?;
+    }
+
+    Ok(rule)
+}
+
+/// The case conversions of serde's `rename_all`, applied to a field name the same way.
+enum RenameRule {
+    Lower,
+    Upper,
+    Pascal,
+    Camel,
+    Snake,
+    ScreamingSnake,
+    Kebab,
+    ScreamingKebab,
+}
+
+impl RenameRule {
+    fn parse(name: &LitStr) -> syn::Result<Self> {
+        Ok(match name.value().as_str() {
+            "lowercase" => RenameRule::Lower,
+            "UPPERCASE" => RenameRule::Upper,
+            "PascalCase" => RenameRule::Pascal,
+            "camelCase" => RenameRule::Camel,
+            "snake_case" => RenameRule::Snake,
+            "SCREAMING_SNAKE_CASE" => RenameRule::ScreamingSnake,
+            "kebab-case" => RenameRule::Kebab,
+            "SCREAMING-KEBAB-CASE" => RenameRule::ScreamingKebab,
+            _ => return Err(syn::Error::new_spanned(name, "unknown rename rule")),
+        })
+    }
+
+    fn apply(&self, field: &str) -> String {
+        match self {
+            RenameRule::Lower | RenameRule::Snake => field.to_string(),
+            RenameRule::Upper | RenameRule::ScreamingSnake => field.to_ascii_uppercase(),
+            RenameRule::Pascal => {
+                let mut pascal = String::new();
+                let mut capitalize = true;
+
+                for ch in field.chars() {
+                    if ch == '_' {
+                        capitalize = true;
+                    } else if capitalize {
+                        pascal.push(ch.to_ascii_uppercase());
+                        capitalize = false;
+                    } else {
+                        pascal.push(ch);
+                    }
+                }
+
+                pascal
+            }
+            RenameRule::Camel => {
+                let pascal = RenameRule::Pascal.apply(field);
+                let mut chars = pascal.chars();
+
+                match chars.next() {
+                    Some(first) => first.to_ascii_lowercase().to_string() + chars.as_str(),
+                    None => pascal,
+                }
+            }
+            RenameRule::Kebab => field.replace('_', "-"),
+            RenameRule::ScreamingKebab => field.to_ascii_uppercase().replace('_', "-"),
+        }
+    }
+}
+
+#[derive(Default)]
+struct FieldOptions {
+    rename: Option<String>,
+    skip: bool,
+}
+
+impl FieldOptions {
+    /// Reads `#[surreal(rename = "...", skip)]`, falling back to the serde attributes that
+    /// change how the field is serialized, so it is stored the same way.
+    fn parse(attrs: &[Attribute]) -> syn::Result<Self> {
+        let mut options = FieldOptions::default();
+        let mut serde = FieldOptions::default();
+
+        for attr in attrs {
+            if attr.path().is_ident("surreal") {
+                attr.parse_nested_meta(|meta| {
+                    if meta.path.is_ident("rename") {
+                        options.rename = Some(meta.value()?.parse::<LitStr>()?.value());
+                        Ok(())
+                    } else if meta.path.is_ident("skip") {
+                        options.skip = true;
+                        Ok(())
+                    } else {
+                        Err(meta.error("expected `rename = \"...\"` or `skip`"))
+                    }
+                })?;
+            } else if attr.path().is_ident("serde") {
+                attr.parse_nested_meta(|meta| {
+                    if meta.path.is_ident("rename") {
+                        if let Some(name) = serialize_name(&meta)? {
+                            serde.rename = Some(name.value());
+                        }
+                    } else if meta.path.is_ident("skip") || meta.path.is_ident("skip_serializing") {
+                        serde.skip = true;
+                    } else if ["flatten", "with", "serialize_with"]
+                        .iter()
+                        .any(|name| meta.path.is_ident(name))
+                    {
+                        // Fields are converted on their own with their `Serialize` impl.
+                        return Err(meta.error(
+                            "SurrealTable does not support this serde attribute, \
+                             implement `SurrealTable` by hand instead",
+                        ));
+                    } else {
+                        // Other serde attributes do not change what is stored.
+                        skip_meta(&meta)?;
+                    }
+
+                    Ok(())
+                })?;
+            }
+        }
+
+        Ok(FieldOptions {
+            rename: options.rename.or(serde.rename),
+            skip: options.skip || serde.skip,
+        })
+    }
+}
+
+/// The name of `rename = "..."` or `rename(serialize = "...")`, and of `rename_all` alike.
+fn serialize_name(meta: &ParseNestedMeta) -> syn::Result<Option<LitStr>> {
+    if meta.input.peek(syn::Token![=]) {
+        return Ok(Some(meta.value()?.parse()?));
+    }
+
+    let mut name = None;
+
+    meta.parse_nested_meta(|meta| {
+        let value = meta.value()?.parse::<LitStr>()?;
+
+        if meta.path.is_ident("serialize") {
+            name = Some(value);
+        }
+
+        Ok(())
+    })?;
+
+    Ok(name)
+}
+
+/// Skips the value of a serde attribute, if it has one.
+fn skip_meta(meta: &ParseNestedMeta) -> syn::Result<()> {
+    if meta.input.peek(syn::Token![=]) {
+        meta.value()?.parse::<proc_macro2::TokenTree>()?;
+    } else if meta.input.peek(syn::token::Paren) {
+        meta.input.parse::<proc_macro2::Group>()?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,189 @@
+use crate::db::error::DatabaseError;
+use crate::db::observer::total_rows;
+use crate::db::runner::Runner;
+use crate::db::{Bindings, BoundQuery, Executor, Query, QueryBuildError, QueryInfo};
+use crate::{InternalResult, Result};
+use log::error;
+use serde::de::DeserializeOwned;
+
+/// Sends several statements in one request and returns their results as a tuple.
+///
+/// Each element of the tuple is taken from the statement at the same index, so it can be any
+/// type a single query result deserializes into, like `Vec<T>` or `Option<T>`. The statements
+/// are not atomic, use a [`Transaction`](crate::db::Transaction) for that.
+///
+/// A batch is never retried, not even with the executor's [`RetryPolicy`](crate::db::RetryPolicy),
+/// as the statements before a failed one were applied and would run twice.
+///
+/// # Examples
+///
+/// ```
+/// # use surrealdb_abstraction::db::{Batch, FieldPath, Query, Select, Statement};
+///
+/// let batch = Batch::new()
+///     .push(Select::query("user").add_condition("active", None, true))
+///     .push(
+///         Select::query("order")
+///             .add_field(FieldPath::raw("count()"), Some("total"))
+///             .group_all(),
+///     )
+///     .try_construct()?;
+///
+/// assert_eq!(
+///     batch.query,
+///     "SELECT * FROM user WHERE active = $p0;\nSELECT count() AS total FROM order  GROUP ALL;"
+/// );
+/// # Ok::<_, surrealdb_abstraction::db::QueryBuildError>(())
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct Batch {
+    statements: Vec<String>,
+    bindings: Bindings,
+    error: Option<QueryBuildError>,
+}
+
+impl Batch {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a statement, its result is the next element of the result tuple.
+    ///
+    /// If the statement cannot be constructed, the batch fails with that error when it is
+    /// constructed or run.
+    pub fn push(mut self, query: impl Query) -> Self {
+        match query.construct_with(&mut self.bindings) {
+            Ok(statement) => {
+                self.statements.push(statement.trim().to_string());
+            }
+            Err(err) => {
+                self.error.get_or_insert(err);
+            }
+        }
+
+        self
+    }
+
+    /// The constructed statements, in the order they were added.
+    pub fn statements(&self) -> &[String] {
+        &self.statements
+    }
+
+    pub fn len(&self) -> usize {
+        self.statements.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.statements.is_empty()
+    }
+
+    pub async fn run<E, R>(self, db: &E) -> Result<R>
+    where
+        E: Executor + ?Sized,
+        R: BatchResults,
+    {
+        self.send(db).await.map_err(Into::into)
+    }
+
+    async fn send<E, R>(self, db: &E) -> InternalResult<R>
+    where
+        E: Executor + ?Sized,
+        R: BatchResults,
+    {
+        if R::LEN != self.len() {
+            return Err(DatabaseError::BatchSizeMismatch {
+                expected: R::LEN,
+                statements: self.len(),
+            });
+        }
+
+        let query = Runner::construct(self)?;
+        let info = QueryInfo::new(&query, None).with_operation("BATCH");
+
+        let attempt = async {
+            let values = Runner::take_all(Runner::send(db, query).await?)?;
+            let rows = total_rows(&values);
+
+            match R::from_values(values) {
+                Ok(results) => Ok((results, rows)),
+                Err(err) => {
+                    error!("Error running batch: {}", err);
+                    Err(err)
+                }
+            }
+        };
+
+        Runner::observe(db, &info, attempt, |(_, rows)| *rows)
+            .await
+            .map(|(results, _)| results)
+    }
+}
+
+impl Query for Batch {
+    fn construct_with(
+        self,
+        bindings: &mut Bindings,
+    ) -> std::result::Result<String, QueryBuildError> {
+        bindings.merge(self.try_construct()?)
+    }
+
+    fn try_construct(self) -> std::result::Result<BoundQuery, QueryBuildError> {
+        if let Some(err) = self.error {
+            return Err(err);
+        }
+
+        let query = self
+            .statements
+            .iter()
+            .map(|statement| format!("{};", statement))
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        Ok(BoundQuery::new(query, self.bindings))
+    }
+}
+
+/// A tuple of results taken from a [`Batch`] response, one element per statement.
+pub trait BatchResults: Sized {
+    /// The number of statements the tuple takes results from.
+    const LEN: usize;
+
+    /// Deserializes the raw results of the statements, in the order they were added.
+    fn from_values(values: Vec<surrealdb::Value>) -> std::result::Result<Self, DatabaseError>;
+}
+
+macro_rules! impl_batch_results {
+    ( $( $len:literal => ( $( $index:tt $name:ident ),+ ) ),+ $(,)? ) => {
+        $(
+            impl<$( $name ),+> BatchResults for ($( $name, )+)
+            where
+                $( $name: DeserializeOwned + 'static, )+
+            {
+                const LEN: usize = $len;
+
+                fn from_values(
+                    mut values: Vec<surrealdb::Value>,
+                ) -> std::result::Result<Self, DatabaseError> {
+                    values.resize_with(Self::LEN, Default::default);
+
+                    Ok(($( Runner::from_value::<$name>(std::mem::take(&mut values[$index]))?, )+))
+                }
+            }
+        )+
+    };
+}
+
+impl_batch_results! {
+    1 => (0 A),
+    2 => (0 A, 1 B),
+    3 => (0 A, 1 B, 2 C),
+    4 => (0 A, 1 B, 2 C, 3 D),
+    5 => (0 A, 1 B, 2 C, 3 D, 4 E),
+    6 => (0 A, 1 B, 2 C, 3 D, 4 E, 5 F),
+    7 => (0 A, 1 B, 2 C, 3 D, 4 E, 5 F, 6 G),
+    8 => (0 A, 1 B, 2 C, 3 D, 4 E, 5 F, 6 G, 7 H),
+    9 => (0 A, 1 B, 2 C, 3 D, 4 E, 5 F, 6 G, 7 H, 8 I),
+    10 => (0 A, 1 B, 2 C, 3 D, 4 E, 5 F, 6 G, 7 H, 8 I, 9 J),
+    11 => (0 A, 1 B, 2 C, 3 D, 4 E, 5 F, 6 G, 7 H, 8 I, 9 J, 10 K),
+    12 => (0 A, 1 B, 2 C, 3 D, 4 E, 5 F, 6 G, 7 H, 8 I, 9 J, 10 K, 11 L),
+}
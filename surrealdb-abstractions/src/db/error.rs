@@ -1,13 +1,426 @@
 use thiserror::Error;
 
+/// The errors of running queries.
+///
+/// Errors SurrealDB reports for common cases are turned into typed variants, like
+/// [`RecordExists`](DatabaseError::RecordExists) or
+/// [`PermissionDenied`](DatabaseError::PermissionDenied), so they can be handled without matching
+/// on the message. Any other error of SurrealDB is a `ResponseError` or `TransactionError`.
 #[derive(Debug, Error)]
 pub enum DatabaseError {
     #[error("Response error: {0}. Possibly the wrong type of response?")]
-    ResponseError(#[from] surrealdb::Error),
+    ResponseError(Box<surrealdb::Error>),
 
     #[error("Transaction error: {0}")]
-    TransactionError(surrealdb::Error),
+    TransactionError(Box<surrealdb::Error>),
+
+    #[error("Query build error: {0}")]
+    QueryBuildError(#[from] QueryBuildError),
+
+    /// The statement at `index` of a transaction failed, `source` is the classified error.
+    #[error("Transaction failed at statement {index} `{statement}`: {source}")]
+    TransactionFailed {
+        index: usize,
+        statement: String,
+        source: Box<DatabaseError>,
+    },
+
+    /// A record with the same id already exists.
+    #[error("Record `{record}` already exists")]
+    RecordExists {
+        table: String,
+        /// The record id, e.g. `user:alice`.
+        record: String,
+    },
+
+    /// A unique index already contains the value of the record being written.
+    #[error("Unique index `{index}` already contains {value}, with record `{record}`")]
+    UniqueViolation {
+        table: String,
+        /// The record that already holds the value.
+        record: String,
+        index: String,
+        value: String,
+    },
+
+    /// A value did not conform to the `ASSERT` clause of its field.
+    #[error(
+        "Field `{field}` of record `{record}` must conform to `{assertion}`, but found {value}"
+    )]
+    AssertionFailed {
+        table: String,
+        record: String,
+        field: String,
+        value: String,
+        assertion: String,
+    },
+
+    /// The session is not allowed to run the query, on `table` if it is known.
+    #[error("Permission denied: {message}")]
+    PermissionDenied {
+        table: Option<String>,
+        message: String,
+    },
+
+    #[error("Query exceeded its timeout")]
+    Timeout,
+
+    /// The transaction conflicted with another one writing the same keys, and can be retried.
+    #[error("Transaction conflict: {0}")]
+    TransactionConflict(String),
+
+    /// A result could not be deserialized into the requested type.
+    #[error("Deserialization error: {0}")]
+    DeserializationError(String),
+
+    #[error("Connection lost: {0}")]
+    ConnectionLost(String),
+
+    #[error("Expected {expected} results, but the batch has {statements} statements")]
+    BatchSizeMismatch { expected: usize, statements: usize },
+
+    #[error("{0} statement returned no record")]
+    EmptyResponse(&'static str),
+
+    #[error("Serialization error: {0}")]
+    SerializationError(String),
+
+    #[error("Migration error: {0}")]
+    MigrationError(#[from] MigrationError),
 
     #[error("Database error: {0}")]
     Error(#[from] anyhow::Error),
 }
+
+impl DatabaseError {
+    /// What kind of error this is, for deciding how to handle it without matching every variant.
+    /// A failed transaction is of the kind of the statement that failed.
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            DatabaseError::TransactionFailed { source, .. } => source.kind(),
+            DatabaseError::RecordExists { .. } => ErrorKind::RecordExists,
+            DatabaseError::UniqueViolation { .. } => ErrorKind::UniqueViolation,
+            DatabaseError::AssertionFailed { .. } => ErrorKind::AssertionFailed,
+            DatabaseError::PermissionDenied { .. } => ErrorKind::PermissionDenied,
+            DatabaseError::Timeout => ErrorKind::Timeout,
+            DatabaseError::TransactionConflict(_) => ErrorKind::TransactionConflict,
+            DatabaseError::DeserializationError(_) => ErrorKind::Deserialization,
+            DatabaseError::SerializationError(_) => ErrorKind::Serialization,
+            DatabaseError::ConnectionLost(_) => ErrorKind::ConnectionLost,
+            DatabaseError::QueryBuildError(_) | DatabaseError::BatchSizeMismatch { .. } => {
+                ErrorKind::QueryBuild
+            }
+            DatabaseError::MigrationError(_) => ErrorKind::Migration,
+            DatabaseError::ResponseError(_)
+            | DatabaseError::TransactionError(_)
+            | DatabaseError::EmptyResponse(_)
+            | DatabaseError::Error(_) => ErrorKind::Other,
+        }
+    }
+
+    /// The typed error for `err`, or `fallback` with it if it is none of the classified cases.
+    pub(crate) fn classify(
+        err: surrealdb::Error,
+        fallback: fn(Box<surrealdb::Error>) -> DatabaseError,
+    ) -> DatabaseError {
+        classify(&err).unwrap_or_else(|| fallback(Box::new(err)))
+    }
+}
+
+/// The kinds of [`DatabaseError`], see [`DatabaseError::kind`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ErrorKind {
+    RecordExists,
+    UniqueViolation,
+    AssertionFailed,
+    PermissionDenied,
+    Timeout,
+    TransactionConflict,
+    Deserialization,
+    Serialization,
+    ConnectionLost,
+    QueryBuild,
+    Migration,
+    Other,
+}
+
+impl ErrorKind {
+    /// The name of the kind in snake case, e.g. `unique_violation`, for logs and metric labels.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ErrorKind::RecordExists => "record_exists",
+            ErrorKind::UniqueViolation => "unique_violation",
+            ErrorKind::AssertionFailed => "assertion_failed",
+            ErrorKind::PermissionDenied => "permission_denied",
+            ErrorKind::Timeout => "timeout",
+            ErrorKind::TransactionConflict => "transaction_conflict",
+            ErrorKind::Deserialization => "deserialization",
+            ErrorKind::Serialization => "serialization",
+            ErrorKind::ConnectionLost => "connection_lost",
+            ErrorKind::QueryBuild => "query_build",
+            ErrorKind::Migration => "migration",
+            ErrorKind::Other => "other",
+        }
+    }
+}
+
+impl From<surrealdb::Error> for DatabaseError {
+    fn from(err: surrealdb::Error) -> Self {
+        DatabaseError::classify(err, DatabaseError::ResponseError)
+    }
+}
+
+fn classify(err: &surrealdb::Error) -> Option<DatabaseError> {
+    use surrealdb::error::{Api, Db};
+
+    let classified = match err {
+        surrealdb::Error::Db(err) => match err {
+            Db::RecordExists { thing } => DatabaseError::RecordExists {
+                table: thing.tb.clone(),
+                record: thing.to_string(),
+            },
+            Db::IndexExists {
+                thing,
+                index,
+                value,
+            } => DatabaseError::UniqueViolation {
+                table: thing.tb.clone(),
+                record: thing.to_string(),
+                index: index.clone(),
+                value: value.clone(),
+            },
+            Db::FieldValue {
+                thing,
+                value,
+                field,
+                check,
+            } => DatabaseError::AssertionFailed {
+                table: table_of(thing),
+                record: thing.clone(),
+                field: field.to_string(),
+                value: value.clone(),
+                assertion: check.clone(),
+            },
+            Db::TablePermissions { table } => DatabaseError::PermissionDenied {
+                table: Some(table.clone()),
+                message: err.to_string(),
+            },
+            Db::ParamPermissions { .. }
+            | Db::FunctionPermissions { .. }
+            | Db::NsNotAllowed { .. }
+            | Db::DbNotAllowed { .. }
+            | Db::IamError(surrealdb::iam::Error::NotAllowed { .. }) => {
+                DatabaseError::PermissionDenied {
+                    table: None,
+                    message: err.to_string(),
+                }
+            }
+            Db::QueryTimedout => DatabaseError::Timeout,
+            // Values are bound as they are, so this is a result that did not fit its type.
+            Db::Serialization(message) => DatabaseError::DeserializationError(message.clone()),
+            // A commit that fails, such as on a conflict, reports its error in this one.
+            Db::Tx(message) | Db::Ds(message) | Db::QueryNotExecutedDetail { message } => {
+                return classify_message(message)
+            }
+            _ => return None,
+        },
+        surrealdb::Error::Api(err) => match err {
+            Api::Query(message) => return classify_message(message),
+            Api::FromValue { .. }
+            | Api::ResponseFromBinary { .. }
+            | Api::FromJsonString { .. }
+            | Api::DeSerializeValue(_)
+            | Api::Deserializer(_) => DatabaseError::DeserializationError(err.to_string()),
+            Api::Http(_) | Api::Ws(_) | Api::ConnectionUninitialised => {
+                DatabaseError::ConnectionLost(err.to_string())
+            }
+            _ => return None,
+        },
+    };
+
+    Some(classified)
+}
+
+/// How the storage engines report a transaction that lost against a concurrent one.
+const CONFLICTS: &[&str] = &[
+    // SurrealKV
+    "Transaction read conflict",
+    "Transaction write conflict",
+    // RocksDB
+    "Resource busy",
+    // TiKV
+    "WriteConflict",
+    // FoundationDB
+    "not committed due to conflict with another transaction",
+];
+
+/// Recognizes the errors of a remote database, which only arrive as their message.
+fn classify_message(message: &str) -> Option<DatabaseError> {
+    if let Some(record) = between(message, "Database record `", "` already exists") {
+        return Some(DatabaseError::RecordExists {
+            table: table_of(record),
+            record: record.to_string(),
+        });
+    }
+
+    if let Some(rest) = message.strip_prefix("Database index `") {
+        if let Some((index, rest)) = rest.split_once("` already contains ") {
+            if let Some((value, record)) = rest.rsplit_once(", with record `") {
+                let record = record.trim_end_matches('`');
+
+                return Some(DatabaseError::UniqueViolation {
+                    table: table_of(record),
+                    record: record.to_string(),
+                    index: index.to_string(),
+                    value: value.to_string(),
+                });
+            }
+        }
+    }
+
+    if let Some(rest) = message.strip_prefix("Found ") {
+        let parts = rest.split_once(" for field `").and_then(|(value, rest)| {
+            let (field, rest) = rest.split_once("`, with record `")?;
+            let (record, assertion) = rest.split_once("`, but field must conform to: ")?;
+
+            Some((value, field, record, assertion))
+        });
+
+        if let Some((value, field, record, assertion)) = parts {
+            return Some(DatabaseError::AssertionFailed {
+                table: table_of(record),
+                record: record.to_string(),
+                field: field.to_string(),
+                value: value.to_string(),
+                assertion: assertion.to_string(),
+            });
+        }
+    }
+
+    if message.starts_with("You don't have permission")
+        || message.starts_with("Not enough permissions")
+    {
+        return Some(DatabaseError::PermissionDenied {
+            table: between(message, "on the `", "` table").map(ToString::to_string),
+            message: message.to_string(),
+        });
+    }
+
+    if message.contains("exceeded the timeout") {
+        return Some(DatabaseError::Timeout);
+    }
+
+    // Only the exact wording of the storage engines, a thrown error or a record id can mention a
+    // conflict as well.
+    if CONFLICTS.iter().any(|conflict| message.contains(conflict)) {
+        return Some(DatabaseError::TransactionConflict(message.to_string()));
+    }
+
+    None
+}
+
+fn between<'a>(message: &'a str, prefix: &str, suffix: &str) -> Option<&'a str> {
+    let start = message.find(prefix)? + prefix.len();
+    let end = start + message[start..].find(suffix)?;
+
+    Some(&message[start..end])
+}
+
+/// The table of a record id like `user:alice`.
+fn table_of(record: &str) -> String {
+    record
+        .split_once(':')
+        .map_or(record, |(table, _)| table)
+        .to_string()
+}
+
+/// The reasons a [`QueryBuilder`](crate::db::QueryBuilder) can fail to construct a query.
+#[derive(Clone, Debug, Error, PartialEq, Eq)]
+pub enum QueryBuildError {
+    #[error("{statement} statement requires a relation, use `relate_items`")]
+    MissingRelation { statement: &'static str },
+
+    #[error("{statement} statement requires content")]
+    MissingContent { statement: &'static str },
+
+    #[error("{statement} statement can only use one of content, PATCH or SET/UNSET")]
+    ConflictingData { statement: &'static str },
+
+    #[error("{statement} statement can select either DIFF or fields, not both")]
+    ConflictingProjection { statement: &'static str },
+
+    #[error("{statement} statement requires a {clause} clause")]
+    MissingClause {
+        statement: &'static str,
+        clause: &'static str,
+    },
+
+    #[error("{statement} statement requires at least one record")]
+    MissingRecords { statement: &'static str },
+
+    #[error("Record of table `{0}` has no id")]
+    MissingRecordId(String),
+
+    #[error("`{id}` is not a valid id for a record of table `{table}`")]
+    InvalidRecordId { table: String, id: String },
+
+    #[error("Table name must not be empty")]
+    EmptyTableName,
+
+    #[error("Invalid identifier `{0}`")]
+    InvalidIdentifier(String),
+
+    #[error("`{0}` is not a field name, use `FieldPath::raw` or an `Expression` for expressions")]
+    ExpressionAsField(String),
+
+    #[error("Graph path must have at least one step")]
+    EmptyGraphPath,
+
+    #[error("Invalid operator `{0}`")]
+    InvalidOperator(String),
+
+    #[error("Cannot paginate by `{0}`, sort keys must be plain fields")]
+    InvalidPaginationOrder(String),
+
+    #[error("Page size must be greater than zero")]
+    ZeroPageSize,
+
+    #[error("Parameter `${0}` is reserved for bound values, use another name")]
+    ReservedParameter(String),
+}
+
+#[derive(Debug, Error)]
+pub enum MigrationError {
+    #[error("Invalid migration file name `{0}`, expected `<version>_<name>.surql`")]
+    InvalidFileName(String),
+
+    #[error("Migration version {0} is defined more than once")]
+    DuplicateVersion(u64),
+
+    #[error("Unknown migration version {0}")]
+    UnknownVersion(u64),
+
+    #[error("Migration {version} `{name}` was changed after it was applied (applied checksum {applied}, current checksum {current})")]
+    ChecksumMismatch {
+        version: u64,
+        name: String,
+        applied: String,
+        current: String,
+    },
+
+    #[error("Migration {version} `{name}` was applied but is no longer defined")]
+    MissingMigration { version: u64, name: String },
+
+    #[error("Migration {version} is pending but migration {latest} was already applied")]
+    OutOfOrder { version: u64, latest: u64 },
+
+    #[error("Cannot migrate down to version {target}, version {latest} is already applied")]
+    CannotMigrateDown { target: u64, latest: u64 },
+
+    #[error("Migration {version} `{name}` failed: {source}")]
+    Failed {
+        version: u64,
+        name: String,
+        source: Box<DatabaseError>,
+    },
+}
@@ -0,0 +1,114 @@
+use crate::db::{BoundQuery, QueryObserver, RetryPolicy};
+use std::ops::Deref;
+use std::rc::Rc;
+use std::sync::{Arc, LazyLock};
+use surrealdb::{Connection, Surreal};
+
+/// Something queries can be run on: a [`Surreal`] client, or anything holding one.
+///
+/// Builders accept any executor, so a plain `&Surreal<C>`, an `Arc`, or a `Lazy` or `LazyLock`
+/// static all work. A `OnceLock` is not an executor, as it has no connection to run on until it
+/// is set: run on the reference `DB.get()` returns instead. Neither is there a transaction
+/// handle, the SurrealDB client has none; run the statements in a
+/// [`Transaction`](crate::db::Transaction). Implement it for wrappers that need to adjust every
+/// query before it is sent.
+///
+/// # Examples
+///
+/// A connection scoped to a tenant, which binds `$tenant` for every query it runs:
+///
+/// ```
+/// # use surrealdb::{Connection, Surreal};
+/// # use surrealdb_abstraction::db::{BoundQuery, Executor};
+/// struct TenantDb<C: Connection> {
+///     db: Surreal<C>,
+///     tenant: String,
+/// }
+///
+/// impl<C: Connection> Executor for TenantDb<C> {
+///     type Connection = C;
+///
+///     fn connection(&self) -> &Surreal<C> {
+///         &self.db
+///     }
+///
+///     fn prepare(&self, mut query: BoundQuery) -> BoundQuery {
+///         query.bindings.insert("tenant", self.tenant.as_str());
+///         query
+///     }
+/// }
+/// ```
+pub trait Executor {
+    type Connection: Connection;
+
+    /// The client queries are sent with.
+    fn connection(&self) -> &Surreal<Self::Connection>;
+
+    /// Called with every constructed query right before it is sent. Returns it unchanged by
+    /// default.
+    fn prepare(&self, query: BoundQuery) -> BoundQuery {
+        query
+    }
+
+    /// How queries that do not set their own [`RetryPolicy`] are retried, not at all by
+    /// default.
+    fn retry_policy(&self) -> Option<&RetryPolicy> {
+        None
+    }
+
+    /// The [`QueryObserver`] every query run on the executor is reported to, none by default.
+    fn observer(&self) -> Option<&dyn QueryObserver> {
+        None
+    }
+}
+
+impl<C> Executor for Surreal<C>
+where
+    C: Connection,
+{
+    type Connection = C;
+
+    fn connection(&self) -> &Surreal<C> {
+        self
+    }
+}
+
+/// Forwards to the executor behind a pointer, given with its generic parameters.
+macro_rules! impl_executor_deref {
+    ( $( [ $( $generics:tt )* ] $pointer:ty ),* $(,)? ) => {
+        $(
+            impl<$( $generics )*> Executor for $pointer
+            where
+                E: Executor,
+            {
+                type Connection = E::Connection;
+
+                fn connection(&self) -> &Surreal<Self::Connection> {
+                    self.deref().connection()
+                }
+
+                fn prepare(&self, query: BoundQuery) -> BoundQuery {
+                    self.deref().prepare(query)
+                }
+
+                fn retry_policy(&self) -> Option<&RetryPolicy> {
+                    self.deref().retry_policy()
+                }
+
+                fn observer(&self) -> Option<&dyn QueryObserver> {
+                    self.deref().observer()
+                }
+            }
+        )*
+    };
+}
+
+impl_executor_deref!(
+    [E: ?Sized] &E,
+    [E: ?Sized] &mut E,
+    [E: ?Sized] Box<E>,
+    [E: ?Sized] Arc<E>,
+    [E: ?Sized] Rc<E>,
+    [E, F: FnOnce() -> E] once_cell::sync::Lazy<E, F>,
+    [E, F: FnOnce() -> E] LazyLock<E, F>,
+);
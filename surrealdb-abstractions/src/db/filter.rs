@@ -1,4 +1,6 @@
+use crate::db::{Bindings, BoundQuery, FieldPath, QueryBuildError};
 use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
 use surrealdb::sql::Value;
 
 type FilterField = String;
@@ -44,8 +46,458 @@ pub fn get_filter_query(filter: &InnerFilter) -> String {
     condition
 }
 
+/// A comparison operator used in a [`Condition`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Operator {
+    /// `=`
+    Eq,
+    /// `!=`
+    NotEq,
+    /// `==`
+    Exact,
+    /// `>`
+    Gt,
+    /// `>=`
+    Gte,
+    /// `<`
+    Lt,
+    /// `<=`
+    Lte,
+    /// `~`
+    Fuzzy,
+    /// `!~`
+    NotFuzzy,
+    /// `IN`
+    In,
+    /// `NOT IN`
+    NotIn,
+    /// `CONTAINS`
+    Contains,
+    /// `CONTAINSNOT`
+    ContainsNot,
+    /// `CONTAINSALL`
+    ContainsAll,
+    /// `CONTAINSANY`
+    ContainsAny,
+    /// `CONTAINSNONE`
+    ContainsNone,
+    /// `INSIDE`
+    Inside,
+    /// `NOTINSIDE`
+    NotInside,
+    /// `ALLINSIDE`
+    AllInside,
+    /// `ANYINSIDE`
+    AnyInside,
+    /// `NONEINSIDE`
+    NoneInside,
+    /// Any other operator, rendered verbatim.
+    Custom(String),
+}
+
+impl Display for Operator {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let operator = match self {
+            Operator::Eq => "=",
+            Operator::NotEq => "!=",
+            Operator::Exact => "==",
+            Operator::Gt => ">",
+            Operator::Gte => ">=",
+            Operator::Lt => "<",
+            Operator::Lte => "<=",
+            Operator::Fuzzy => "~",
+            Operator::NotFuzzy => "!~",
+            Operator::In => "IN",
+            Operator::NotIn => "NOT IN",
+            Operator::Contains => "CONTAINS",
+            Operator::ContainsNot => "CONTAINSNOT",
+            Operator::ContainsAll => "CONTAINSALL",
+            Operator::ContainsAny => "CONTAINSANY",
+            Operator::ContainsNone => "CONTAINSNONE",
+            Operator::Inside => "INSIDE",
+            Operator::NotInside => "NOTINSIDE",
+            Operator::AllInside => "ALLINSIDE",
+            Operator::AnyInside => "ANYINSIDE",
+            Operator::NoneInside => "NONEINSIDE",
+            Operator::Custom(operator) => operator,
+        };
+
+        f.write_str(operator)
+    }
+}
+
+impl Operator {
+    /// Checks that a [`Operator::Custom`] operator is one SurrealQL knows, so it cannot smuggle
+    /// anything else (such as a `--` comment) into the query.
+    pub fn validate(&self) -> Result<(), QueryBuildError> {
+        if let Operator::Custom(operator) = self {
+            let upper = operator.trim().to_uppercase();
+            let valid = CUSTOM_OPERATORS.contains(&upper.as_str())
+                || upper
+                    .strip_prefix('@')
+                    .and_then(|rest| rest.strip_suffix('@'))
+                    .is_some_and(|n| !n.is_empty() && n.chars().all(|c| c.is_ascii_digit()));
+
+            if !valid {
+                return Err(QueryBuildError::InvalidOperator(operator.clone()));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// SurrealQL operators accepted by [`Operator::Custom`], besides the `@N@` match operators.
+const CUSTOM_OPERATORS: &[&str] = &[
+    "=",
+    "!=",
+    "==",
+    "?=",
+    "*=",
+    "~",
+    "!~",
+    "?~",
+    "*~",
+    "<",
+    "<=",
+    ">",
+    ">=",
+    "+",
+    "-",
+    "*",
+    "/",
+    "**",
+    "@@",
+    "&&",
+    "||",
+    "??",
+    "?:",
+    "AND",
+    "OR",
+    "IS",
+    "IS NOT",
+    "IN",
+    "NOT IN",
+    "CONTAINS",
+    "CONTAINSNOT",
+    "CONTAINSALL",
+    "CONTAINSANY",
+    "CONTAINSNONE",
+    "INSIDE",
+    "NOTINSIDE",
+    "ALLINSIDE",
+    "ANYINSIDE",
+    "NONEINSIDE",
+    "OUTSIDE",
+    "INTERSECTS",
+];
+
+impl From<&str> for Operator {
+    fn from(operator: &str) -> Self {
+        match operator.trim().to_uppercase().as_str() {
+            "=" | "IS" => Operator::Eq,
+            "!=" | "IS NOT" => Operator::NotEq,
+            "==" => Operator::Exact,
+            ">" => Operator::Gt,
+            ">=" => Operator::Gte,
+            "<" => Operator::Lt,
+            "<=" => Operator::Lte,
+            "~" => Operator::Fuzzy,
+            "!~" => Operator::NotFuzzy,
+            "IN" => Operator::In,
+            "NOT IN" => Operator::NotIn,
+            "CONTAINS" => Operator::Contains,
+            "CONTAINSNOT" => Operator::ContainsNot,
+            "CONTAINSALL" => Operator::ContainsAll,
+            "CONTAINSANY" => Operator::ContainsAny,
+            "CONTAINSNONE" => Operator::ContainsNone,
+            "INSIDE" => Operator::Inside,
+            "NOTINSIDE" => Operator::NotInside,
+            "ALLINSIDE" => Operator::AllInside,
+            "ANYINSIDE" => Operator::AnyInside,
+            "NONEINSIDE" => Operator::NoneInside,
+            _ => Operator::Custom(operator.to_string()),
+        }
+    }
+}
+
+impl From<String> for Operator {
+    fn from(operator: String) -> Self {
+        Operator::from(operator.as_str())
+    }
+}
+
+/// A node in the boolean expression tree of a [`Filter`].
+///
+/// Nested `AND`/`OR` groups are always parenthesised when rendered, so the tree structure is
+/// kept regardless of operator precedence.
+///
+/// # Examples
+///
+/// ```
+/// # use surrealdb_abstraction::db::{Condition, Filter};
+///
+/// let filter = Filter::new(Condition::eq("status", "a").or(Condition::eq("status", "b")))
+///     .and(Condition::eq("owner", "me"))
+///     .try_construct()?;
+///
+/// assert_eq!(filter.query, "WHERE (status = $p0 OR status = $p1) AND owner = $p2");
+/// # Ok::<_, surrealdb_abstraction::db::QueryBuildError>(())
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub enum Condition {
+    /// `field <operator> value`
+    Compare {
+        field: FieldPath,
+        operator: Operator,
+        value: Value,
+    },
+    /// `field IS NONE`
+    IsNone(FieldPath),
+    /// `field IS NOT NONE`
+    IsNotNone(FieldPath),
+    /// All conditions must hold.
+    And(Vec<Condition>),
+    /// At least one condition must hold.
+    Or(Vec<Condition>),
+    /// `!(condition)`
+    Not(Box<Condition>),
+}
+
+macro_rules! impl_compare {
+    ( $( $name:ident => $operator:ident ),* $(,)? ) => {
+        $(
+            #[doc = concat!("`field ", stringify!($operator), " value`")]
+            pub fn $name<T>(field: impl Into<FieldPath>, value: T) -> Self
+            where
+                Value: From<T>,
+            {
+                Self::compare(field, Operator::$operator, value)
+            }
+        )*
+    };
+}
+
+impl Condition {
+    pub fn compare<T>(field: impl Into<FieldPath>, operator: impl Into<Operator>, value: T) -> Self
+    where
+        Value: From<T>,
+    {
+        Condition::Compare {
+            field: field.into(),
+            operator: operator.into(),
+            value: Value::from(value),
+        }
+    }
+
+    impl_compare! {
+        eq => Eq,
+        not_eq => NotEq,
+        exact => Exact,
+        gt => Gt,
+        gte => Gte,
+        lt => Lt,
+        lte => Lte,
+        fuzzy => Fuzzy,
+        not_fuzzy => NotFuzzy,
+        is_in => In,
+        not_in => NotIn,
+        contains => Contains,
+        contains_not => ContainsNot,
+        contains_all => ContainsAll,
+        contains_any => ContainsAny,
+        contains_none => ContainsNone,
+        inside => Inside,
+        not_inside => NotInside,
+        all_inside => AllInside,
+        any_inside => AnyInside,
+        none_inside => NoneInside,
+    }
+
+    pub fn is_none(field: impl Into<FieldPath>) -> Self {
+        Condition::IsNone(field.into())
+    }
+
+    pub fn is_not_none(field: impl Into<FieldPath>) -> Self {
+        Condition::IsNotNone(field.into())
+    }
+
+    /// Combines this condition with `other` using `AND`, flattening nested `AND`s.
+    pub fn and(self, other: Condition) -> Self {
+        match self {
+            Condition::And(mut conditions) => {
+                conditions.push(other);
+                Condition::And(conditions)
+            }
+            condition => Condition::And(vec![condition, other]),
+        }
+    }
+
+    /// Combines this condition with `other` using `OR`, flattening nested `OR`s.
+    pub fn or(self, other: Condition) -> Self {
+        match self {
+            Condition::Or(mut conditions) => {
+                conditions.push(other);
+                Condition::Or(conditions)
+            }
+            condition => Condition::Or(vec![condition, other]),
+        }
+    }
+
+    /// Negates this condition.
+    pub fn negate(self) -> Self {
+        Condition::Not(Box::new(self))
+    }
+
+    /// Returns the field this condition tests, if it is a single comparison or `IS NONE` check.
+    pub fn field(&self) -> Option<&FieldPath> {
+        match self {
+            Condition::Compare { field, .. } => Some(field),
+            Condition::IsNone(field) | Condition::IsNotNone(field) => Some(field),
+            _ => None,
+        }
+    }
+
+    fn is_compare(&self, field: &FieldPath, operator: &Operator) -> bool {
+        matches!(self, Condition::Compare { field: f, operator: o, .. } if f == field && o == operator)
+    }
+
+    /// Sets the value of every `field <operator> value` comparison this condition requires, i.e.
+    /// itself or one of its `AND` conjuncts, returning whether any matched. Comparisons inside
+    /// `OR` and `NOT` are left alone, as changing their value changes what they mean.
+    fn replace_conjuncts(&mut self, field: &FieldPath, operator: &Operator, value: &Value) -> bool {
+        match self {
+            Condition::Compare {
+                field: f,
+                operator: o,
+                value: v,
+            } if f == field && o == operator => {
+                *v = value.clone();
+                true
+            }
+            Condition::And(conditions) => {
+                let mut replaced = false;
+
+                for condition in conditions {
+                    replaced |= condition.replace_conjuncts(field, operator, value);
+                }
+
+                replaced
+            }
+            _ => false,
+        }
+    }
+
+    /// Removes every condition matching `predicate`, dropping groups that end up empty.
+    fn remove_where<P>(self, predicate: &P) -> Option<Condition>
+    where
+        P: Fn(&Condition) -> bool,
+    {
+        if predicate(&self) {
+            return None;
+        }
+
+        match self {
+            Condition::And(conditions) => Self::collapse(conditions, predicate, Condition::And),
+            Condition::Or(conditions) => Self::collapse(conditions, predicate, Condition::Or),
+            Condition::Not(condition) => condition.remove_where(predicate).map(Condition::negate),
+            condition => Some(condition),
+        }
+    }
+
+    fn collapse<P>(
+        conditions: Vec<Condition>,
+        predicate: &P,
+        group: fn(Vec<Condition>) -> Condition,
+    ) -> Option<Condition>
+    where
+        P: Fn(&Condition) -> bool,
+    {
+        let mut conditions = conditions
+            .into_iter()
+            .filter_map(|condition| condition.remove_where(predicate))
+            .collect::<Vec<_>>();
+
+        match conditions.len() {
+            0 => None,
+            1 => conditions.pop(),
+            _ => Some(group(conditions)),
+        }
+    }
+
+    /// Renders the condition, binding every value in `bindings`.
+    pub fn construct_with(self, bindings: &mut Bindings) -> Result<String, QueryBuildError> {
+        let condition = match self {
+            Condition::Compare {
+                field,
+                operator,
+                value,
+            } => {
+                field.validate()?;
+                operator.validate()?;
+
+                format!("{} {} {}", field, operator, bindings.bind(value))
+            }
+            Condition::IsNone(field) => {
+                field.validate()?;
+
+                format!("{} IS NONE", field)
+            }
+            Condition::IsNotNone(field) => {
+                field.validate()?;
+
+                format!("{} IS NOT NONE", field)
+            }
+            Condition::And(conditions) => Self::join(conditions, " AND ", "true", bindings)?,
+            Condition::Or(conditions) => Self::join(conditions, " OR ", "false", bindings)?,
+            Condition::Not(condition) => format!("!({})", condition.construct_with(bindings)?),
+        };
+
+        Ok(condition)
+    }
+
+    fn join(
+        conditions: Vec<Condition>,
+        separator: &str,
+        empty: &str,
+        bindings: &mut Bindings,
+    ) -> Result<String, QueryBuildError> {
+        if conditions.is_empty() {
+            return Ok(empty.to_string());
+        }
+
+        let conditions = conditions
+            .into_iter()
+            .map(|condition| match condition {
+                Condition::And(_) | Condition::Or(_) => {
+                    Ok(format!("({})", condition.construct_with(bindings)?))
+                }
+                condition => condition.construct_with(bindings),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(conditions.join(separator))
+    }
+}
+
+impl std::ops::Not for Condition {
+    type Output = Condition;
+
+    fn not(self) -> Self::Output {
+        self.negate()
+    }
+}
+
 /// Represents a filter for the database.
 ///
+/// The filter is a tree of [`Condition`]s. Conditions added with [`Filter::add_condition`] or
+/// [`Filter::and`] are joined with `AND`, [`Filter::or`] joins the whole filter so far with
+/// another condition using `OR`.
+///
+/// Conditions are rendered in the order they were added and repeated conditions on the same
+/// field are all kept. Use [`Filter::replace_condition`] or [`Filter::remove_condition`] to
+/// change an existing condition on purpose.
+///
 /// # Examples
 ///
 /// ```
@@ -54,63 +506,176 @@ pub fn get_filter_query(filter: &InnerFilter) -> String {
 /// let filter = Filter::default()
 ///     .add_condition("username".to_string(), None, "admin")
 ///     .add_condition("age".to_string(), Some(">".to_string()), 18)
-///     .construct();
+///     .try_construct()?;
 ///
-/// assert_eq!(filter, "WHERE age > 18 AND username = 'admin'".to_string());
+/// assert_eq!(filter.query, "WHERE username = $p0 AND age > $p1".to_string());
+/// assert_eq!(filter.bindings.get("p0"), Some(&"admin".into()));
+/// # Ok::<_, surrealdb_abstraction::db::QueryBuildError>(())
 /// ```
 ///
-#[derive(Clone, Debug, Default)]
-pub struct Filter(InnerFilter);
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Filter(Option<Condition>);
 
 impl Filter {
+    pub fn new(condition: Condition) -> Self {
+        Self(Some(condition))
+    }
+
     pub fn add_condition<T>(
+        self,
+        field: impl Into<FieldPath>,
+        operator: FilterOperator,
+        value: T,
+    ) -> Self
+    where
+        Value: From<T>,
+    {
+        let operator = operator.map(Operator::from).unwrap_or(Operator::Eq);
+
+        self.and(Condition::compare(field, operator, value))
+    }
+
+    /// Adds `condition`, joined with `AND`.
+    pub fn and(mut self, condition: Condition) -> Self {
+        self.0 = Some(match self.0.take() {
+            Some(root) => root.and(condition),
+            None => condition,
+        });
+
+        self
+    }
+
+    /// Joins the filter so far with `condition` using `OR`.
+    pub fn or(mut self, condition: Condition) -> Self {
+        self.0 = Some(match self.0.take() {
+            Some(root) => root.or(condition),
+            None => condition,
+        });
+
+        self
+    }
+
+    /// Sets the value of every `field <operator> value` comparison joined to the filter with
+    /// `AND`. If there is none, the condition is added instead.
+    ///
+    /// Comparisons inside an `OR` or `NOT` group are not changed, so the condition is added
+    /// alongside them.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use surrealdb_abstraction::db::{Condition, Filter};
+    ///
+    /// let filter = Filter::new(Condition::eq("role", "admin").or(Condition::eq("role", "owner")))
+    ///     .replace_condition("role", None, "guest")
+    ///     .try_construct()?;
+    ///
+    /// assert_eq!(filter.query, "WHERE (role = $p0 OR role = $p1) AND role = $p2");
+    /// assert_eq!(filter.bindings.get("p2"), Some(&"guest".into()));
+    /// # Ok::<_, surrealdb_abstraction::db::QueryBuildError>(())
+    /// ```
+    pub fn replace_condition<T>(
         mut self,
-        field: FilterField,
+        field: impl Into<FieldPath>,
         operator: FilterOperator,
         value: T,
     ) -> Self
     where
         Value: From<T>,
     {
+        let field = field.into();
+        let operator = operator.map(Operator::from).unwrap_or(Operator::Eq);
         let value = Value::from(value);
 
-        self.0.insert((field, operator), value);
+        let replaced = match &mut self.0 {
+            Some(root) => root.replace_conjuncts(&field, &operator, &value),
+            None => false,
+        };
+
+        if replaced {
+            self
+        } else {
+            self.and(Condition::compare::<Value>(field, operator, value))
+        }
+    }
+
+    /// Removes every `field <operator> value` comparison from the filter.
+    pub fn remove_condition(
+        mut self,
+        field: impl Into<FieldPath>,
+        operator: FilterOperator,
+    ) -> Self {
+        let field = field.into();
+        let operator = operator.map(Operator::from).unwrap_or(Operator::Eq);
+
+        self.0 = self
+            .0
+            .and_then(|root| root.remove_where(&|c| c.is_compare(&field, &operator)));
 
         self
     }
 
-    pub fn construct(self) -> String {
-        let mut condition = String::from("WHERE ");
+    /// Removes every condition testing `field`, whatever its operator.
+    pub fn remove_field(mut self, field: impl Into<FieldPath>) -> Self {
+        let field = field.into();
 
-        let mut conditions = Vec::new();
+        self.0 = self
+            .0
+            .and_then(|root| root.remove_where(&|c| c.field() == Some(&field)));
 
-        for ((key, operator), value) in self.0 {
-            conditions.push(format!(
-                "{} {} {}",
-                key,
-                operator.unwrap_or("=".to_string()),
-                value
-            ));
-        }
+        self
+    }
 
-        if !conditions.is_empty() {
-            condition.push_str(&conditions.join(" AND "));
-        } else {
-            condition = String::new();
+    pub fn is_empty(&self) -> bool {
+        self.0.is_none()
+    }
+
+    pub fn condition(&self) -> Option<&Condition> {
+        self.0.as_ref()
+    }
+
+    /// Constructs the WHERE clause. Prefer [`Filter::try_construct`], which returns the error
+    /// instead.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a field name is empty or contains control characters, or a custom operator is
+    /// not made of operator symbols or a single keyword, see [`Operator::validate`].
+    pub fn construct(self) -> BoundQuery {
+        match self.try_construct() {
+            Ok(query) => query,
+            Err(err) => panic!("{}", err),
         }
+    }
 
-        condition
+    pub fn try_construct(self) -> Result<BoundQuery, QueryBuildError> {
+        let mut bindings = Bindings::default();
+        let query = self.construct_with(&mut bindings)?;
+
+        Ok(BoundQuery::new(query, bindings))
+    }
+
+    /// Returns the WHERE clause, binding every value in `bindings`.
+    pub fn construct_with(self, bindings: &mut Bindings) -> Result<String, QueryBuildError> {
+        match self.0 {
+            Some(condition) => Ok(format!("WHERE {}", condition.construct_with(bindings)?)),
+            None => Ok(String::new()),
+        }
     }
 }
 
-impl From<Filter> for InnerFilter {
-    fn from(filter: Filter) -> Self {
-        filter.0
+impl From<Condition> for Filter {
+    fn from(condition: Condition) -> Self {
+        Self::new(condition)
     }
 }
 
 impl From<InnerFilter> for Filter {
     fn from(filter: InnerFilter) -> Self {
-        Self(filter)
+        filter
+            .into_iter()
+            .fold(Filter::default(), |filter, ((field, operator), value)| {
+                filter.add_condition(field, operator, value)
+            })
     }
 }
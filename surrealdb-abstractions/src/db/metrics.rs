@@ -0,0 +1,365 @@
+use crate::db::{DatabaseError, ErrorKind, QueryInfo, QueryObserver};
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::{Mutex, MutexGuard, PoisonError};
+use std::time::Duration;
+
+/// The upper bounds of the latency buckets, the Prometheus client defaults.
+const DEFAULT_BUCKETS: [f64; 11] = [
+    0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0,
+];
+
+/// Collects counters and latency histograms of the queries run on a connection.
+///
+/// It is a [`QueryObserver`], so share it with [`Observed`](crate::db::Observed) behind an
+/// `Arc` and read it with [`snapshot`](Self::snapshot). Every attempt of a retried query is
+/// counted on its own.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use std::sync::Arc;
+/// # use surrealdb::engine::local::Db;
+/// # use surrealdb::Surreal;
+/// # use surrealdb_abstraction::db::{Observed, QueryMetrics, Select, Statement};
+/// # #[derive(serde::Deserialize)]
+/// # struct User { name: String }
+/// # async fn example(db: Surreal<Db>) -> surrealdb_abstraction::Result<()> {
+/// let metrics = Arc::new(QueryMetrics::new());
+/// let db = Observed::new(db, Arc::clone(&metrics));
+///
+/// let users: Vec<User> = Select::query("user").run(&db, 0).await?;
+///
+/// let snapshot = metrics.snapshot();
+/// assert_eq!(snapshot.queries("SELECT", Some("user")), 1);
+///
+/// // Served on `/metrics`.
+/// let body = snapshot.to_prometheus();
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct QueryMetrics {
+    buckets: Vec<Duration>,
+    state: Mutex<MetricsSnapshot>,
+}
+
+impl Default for QueryMetrics {
+    fn default() -> Self {
+        Self::with_buckets(DEFAULT_BUCKETS.iter().copied().map(Duration::from_secs_f64))
+    }
+}
+
+impl QueryMetrics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Collects latencies into buckets with the given upper bounds instead of the defaults,
+    /// which range from 5ms to 10s.
+    pub fn with_buckets(buckets: impl IntoIterator<Item = Duration>) -> Self {
+        let mut buckets = buckets.into_iter().collect::<Vec<_>>();
+        buckets.sort();
+        buckets.dedup();
+
+        Self {
+            buckets,
+            state: Mutex::new(MetricsSnapshot::default()),
+        }
+    }
+
+    /// A copy of the metrics collected so far.
+    pub fn snapshot(&self) -> MetricsSnapshot {
+        self.state().clone()
+    }
+
+    /// Clears the metrics collected so far.
+    pub fn reset(&self) {
+        *self.state() = MetricsSnapshot::default();
+    }
+
+    fn state(&self) -> MutexGuard<'_, MetricsSnapshot> {
+        // The counters stay consistent even if a thread panicked while holding the lock.
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Counts a query, and the kind of its error if it failed, under a single lock.
+    fn record(
+        &self,
+        query: &QueryInfo,
+        duration: Duration,
+        rows: Option<u64>,
+        error: Option<ErrorKind>,
+    ) {
+        let labels = QueryLabels {
+            operation: query.operation().to_string(),
+            table: query.table().map(ToString::to_string),
+        };
+
+        let mut state = self.state();
+        let stats = state
+            .queries
+            .entry(labels)
+            .or_insert_with(|| QueryStats::new(&self.buckets));
+
+        stats.count += 1;
+        stats.errors += error.is_some() as u64;
+        stats.rows += rows.unwrap_or_default();
+        stats.latency.observe(duration);
+
+        if let Some(kind) = error {
+            *state.errors.entry(kind).or_default() += 1;
+        }
+    }
+}
+
+impl QueryObserver for QueryMetrics {
+    fn after_execute(&self, query: &QueryInfo, duration: Duration, rows: Option<u64>) {
+        self.record(query, duration, rows, None);
+    }
+
+    fn on_error(&self, query: &QueryInfo, duration: Duration, error: &DatabaseError) {
+        self.record(query, duration, None, Some(error.kind()));
+    }
+}
+
+/// The metrics of a [`QueryMetrics`] at one point in time.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MetricsSnapshot {
+    /// The statistics of every statement type and table queried.
+    pub queries: BTreeMap<QueryLabels, QueryStats>,
+    /// The number of failed queries by the kind of their error.
+    pub errors: BTreeMap<ErrorKind, u64>,
+}
+
+impl MetricsSnapshot {
+    /// The number of `operation` queries run on `table`, including failed ones.
+    pub fn queries(&self, operation: &str, table: Option<&str>) -> u64 {
+        self.stats(operation, table).map_or(0, |stats| stats.count)
+    }
+
+    /// The number of failed queries with an error of `kind`.
+    pub fn errors(&self, kind: ErrorKind) -> u64 {
+        self.errors.get(&kind).copied().unwrap_or_default()
+    }
+
+    pub fn stats(&self, operation: &str, table: Option<&str>) -> Option<&QueryStats> {
+        self.queries
+            .iter()
+            .find(|(labels, _)| labels.operation == operation && labels.table.as_deref() == table)
+            .map(|(_, stats)| stats)
+    }
+
+    /// The metrics in the Prometheus text exposition format, with `operation` and `table`
+    /// labels, `table` being empty for queries not built for a single table.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use surrealdb_abstraction::db::MetricsSnapshot;
+    /// let text = MetricsSnapshot::default().to_prometheus();
+    ///
+    /// assert!(text.contains("# TYPE surrealdb_queries_total counter"));
+    /// ```
+    pub fn to_prometheus(&self) -> String {
+        let mut out = String::new();
+
+        // Writing to a `String` cannot fail.
+        let _ = self.write_prometheus(&mut out);
+
+        out
+    }
+
+    fn write_prometheus(&self, out: &mut String) -> std::fmt::Result {
+        writeln!(
+            out,
+            "# HELP surrealdb_queries_total Queries run, including failed ones."
+        )?;
+        writeln!(out, "# TYPE surrealdb_queries_total counter")?;
+        for (labels, stats) in &self.queries {
+            writeln!(out, "surrealdb_queries_total{{{}}} {}", labels, stats.count)?;
+        }
+
+        writeln!(
+            out,
+            "# HELP surrealdb_query_failures_total Queries that failed."
+        )?;
+        writeln!(out, "# TYPE surrealdb_query_failures_total counter")?;
+        for (labels, stats) in &self.queries {
+            writeln!(
+                out,
+                "surrealdb_query_failures_total{{{}}} {}",
+                labels, stats.errors
+            )?;
+        }
+
+        writeln!(
+            out,
+            "# HELP surrealdb_query_rows_total Records returned by queries."
+        )?;
+        writeln!(out, "# TYPE surrealdb_query_rows_total counter")?;
+        for (labels, stats) in &self.queries {
+            writeln!(
+                out,
+                "surrealdb_query_rows_total{{{}}} {}",
+                labels, stats.rows
+            )?;
+        }
+
+        writeln!(
+            out,
+            "# HELP surrealdb_query_errors_total Failed queries by kind of error."
+        )?;
+        writeln!(out, "# TYPE surrealdb_query_errors_total counter")?;
+        for (kind, count) in &self.errors {
+            writeln!(
+                out,
+                "surrealdb_query_errors_total{{kind=\"{}\"}} {}",
+                kind.as_str(),
+                count
+            )?;
+        }
+
+        writeln!(
+            out,
+            "# HELP surrealdb_query_duration_seconds Time taken by queries."
+        )?;
+        writeln!(out, "# TYPE surrealdb_query_duration_seconds histogram")?;
+        for (labels, stats) in &self.queries {
+            let latency = &stats.latency;
+
+            for (bound, count) in latency.buckets() {
+                writeln!(
+                    out,
+                    "surrealdb_query_duration_seconds_bucket{{{},le=\"{}\"}} {}",
+                    labels,
+                    bound.as_secs_f64(),
+                    count
+                )?;
+            }
+            writeln!(
+                out,
+                "surrealdb_query_duration_seconds_bucket{{{},le=\"+Inf\"}} {}",
+                labels, latency.count
+            )?;
+            writeln!(
+                out,
+                "surrealdb_query_duration_seconds_sum{{{}}} {}",
+                labels,
+                latency.sum.as_secs_f64()
+            )?;
+            writeln!(
+                out,
+                "surrealdb_query_duration_seconds_count{{{}}} {}",
+                labels, latency.count
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+/// The statement type and table metrics are collected by, see [`QueryInfo`].
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct QueryLabels {
+    pub operation: String,
+    pub table: Option<String>,
+}
+
+/// Formats the labels for Prometheus, e.g. `operation="SELECT",table="user"`.
+impl std::fmt::Display for QueryLabels {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "operation=\"{}\",table=\"{}\"",
+            escape_label(&self.operation),
+            escape_label(self.table.as_deref().unwrap_or_default())
+        )
+    }
+}
+
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+/// The statistics of the queries of one [`QueryLabels`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct QueryStats {
+    /// The number of queries run, including failed ones.
+    pub count: u64,
+    pub errors: u64,
+    /// The number of records returned.
+    pub rows: u64,
+    pub latency: Histogram,
+}
+
+impl QueryStats {
+    fn new(buckets: &[Duration]) -> Self {
+        Self {
+            count: 0,
+            errors: 0,
+            rows: 0,
+            latency: Histogram::new(buckets),
+        }
+    }
+}
+
+/// Query durations counted into buckets by their upper bound.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Histogram {
+    bounds: Vec<Duration>,
+    /// The number of durations in each bucket and not in a smaller one.
+    counts: Vec<u64>,
+    count: u64,
+    sum: Duration,
+}
+
+impl Histogram {
+    fn new(bounds: &[Duration]) -> Self {
+        Self {
+            bounds: bounds.to_vec(),
+            counts: vec![0; bounds.len()],
+            count: 0,
+            sum: Duration::ZERO,
+        }
+    }
+
+    fn observe(&mut self, duration: Duration) {
+        if let Some(bucket) = self.bounds.iter().position(|bound| duration <= *bound) {
+            self.counts[bucket] += 1;
+        }
+
+        self.count += 1;
+        self.sum += duration;
+    }
+
+    /// The upper bound of every bucket with the number of durations up to it.
+    pub fn buckets(&self) -> impl Iterator<Item = (Duration, u64)> + '_ {
+        self.bounds
+            .iter()
+            .zip(self.counts.iter().scan(0, |total, count| {
+                *total += count;
+                Some(*total)
+            }))
+            .map(|(bound, count)| (*bound, count))
+    }
+
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    pub fn sum(&self) -> Duration {
+        self.sum
+    }
+
+    /// The average duration, zero if nothing was observed.
+    pub fn mean(&self) -> Duration {
+        match self.count {
+            0 => Duration::ZERO,
+            count => Duration::from_secs_f64(self.sum.as_secs_f64() / count as f64),
+        }
+    }
+}
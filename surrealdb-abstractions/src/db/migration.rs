@@ -0,0 +1,589 @@
+use crate::db::error::{DatabaseError, MigrationError};
+use crate::db::runner::Runner;
+use crate::db::{
+    Bindings, BoundQuery, Executor, OrderBy, Query, QueryBuildError, Select, Statement, Transaction,
+};
+use crate::{InternalResult, Result};
+use log::info;
+use sha2::{Digest, Sha256};
+use std::fmt::{Display, Formatter};
+use surrealdb::sql::{Datetime, Id, Thing};
+
+/// The table applied migrations are recorded in.
+pub const MIGRATIONS_TABLE: &str = "_migrations";
+
+/// Embeds a `<version>_<name>.surql` file as a [`Migration`], the path is relative to the file
+/// the macro is used in.
+///
+/// ```ignore
+/// let migration = embed_migration!("../migrations/0001_create_users.surql")?;
+/// ```
+#[macro_export]
+macro_rules! embed_migration {
+    ($path:literal) => {
+        $crate::db::Migration::embedded($path, include_str!($path))
+    };
+}
+
+/// A versioned set of statements that changes the schema or data.
+///
+/// Migrations are either written in SurrealQL, usually embedded `.surql` files, or built from
+/// statement builders. Their checksum is taken over the SurrealQL they run, so changing a
+/// migration after it was applied is detected by the [`Migrator`].
+///
+/// The SurrealQL of a builder migration is rendered by this crate, so an upgrade that renders a
+/// statement differently changes its checksum, even though the migration did not change. Write
+/// migrations that must keep their checksum in SurrealQL, or accept the new checksums with
+/// [`Migrator::repair`].
+///
+/// # Examples
+///
+/// ```
+/// # use surrealdb::sql::Kind;
+/// # use surrealdb_abstraction::db::{DefineField, DefineTable, Migration};
+///
+/// let migration = Migration::new(1, "create_users")
+///     .statement(DefineTable::new("user").schemafull())
+///     .statement(DefineField::new("name", "user").kind(Kind::String));
+///
+/// assert_eq!(
+///     migration.surql()?,
+///     "DEFINE TABLE user SCHEMAFULL;\nDEFINE FIELD name ON TABLE user TYPE string;"
+/// );
+///
+/// let migration = Migration::embedded(
+///     "migrations/0002_add_age.surql",
+///     "DEFINE FIELD age ON TABLE user TYPE int;",
+/// )?;
+///
+/// assert_eq!(migration.version(), 2);
+/// assert_eq!(migration.name(), "add_age");
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+#[derive(Clone, Debug)]
+pub struct Migration {
+    version: u64,
+    name: String,
+    statements: Vec<BoundQuery>,
+    error: Option<QueryBuildError>,
+}
+
+impl Migration {
+    pub fn new(version: u64, name: impl Into<String>) -> Self {
+        Self {
+            version,
+            name: name.into(),
+            statements: Vec::new(),
+            error: None,
+        }
+    }
+
+    /// A migration written in SurrealQL. The statements must not start or commit a transaction,
+    /// the migration already runs in one, and must not use parameters named like `$p0`, which
+    /// are reserved for bound values.
+    pub fn from_surql(version: u64, name: impl Into<String>, surql: &str) -> Self {
+        Self::new(version, name).statement(surql.trim().trim_end_matches(';').trim())
+    }
+
+    /// A migration from a file named `<version>_<name>.surql`, e.g. `0001_create_users.surql`,
+    /// see [`embed_migration!`](crate::embed_migration).
+    pub fn embedded(path: &str, surql: &str) -> std::result::Result<Self, MigrationError> {
+        let invalid = || MigrationError::InvalidFileName(path.to_string());
+
+        let file_name = path.rsplit(['/', '\\']).next().unwrap_or(path);
+        let stem = file_name.strip_suffix(".surql").ok_or_else(invalid)?;
+        let (version, name) = stem.split_once('_').ok_or_else(invalid)?;
+        let version = version.parse().map_err(|_| invalid())?;
+
+        if name.is_empty() {
+            return Err(invalid());
+        }
+
+        Ok(Self::from_surql(version, name, surql))
+    }
+
+    /// Adds a statement, statements run in the order they were added.
+    ///
+    /// If the statement cannot be constructed, the migration fails with that error when it is
+    /// planned or run.
+    pub fn statement(mut self, query: impl Query) -> Self {
+        match query.try_construct() {
+            Ok(query) => self.statements.push(query),
+            Err(err) => {
+                self.error.get_or_insert(err);
+            }
+        }
+
+        self
+    }
+
+    pub fn version(&self) -> u64 {
+        self.version
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The SurrealQL the migration runs, with every value written in place.
+    pub fn surql(&self) -> std::result::Result<String, QueryBuildError> {
+        if let Some(err) = &self.error {
+            return Err(err.clone());
+        }
+
+        Ok(self
+            .statements
+            .iter()
+            .map(|statement| format!("{};", statement.clone().inline().trim()))
+            .collect::<Vec<_>>()
+            .join("\n"))
+    }
+
+    /// The SHA-256 of [`surql`](Self::surql), as hex.
+    pub fn checksum(&self) -> std::result::Result<String, QueryBuildError> {
+        let digest = Sha256::digest(self.surql()?.as_bytes());
+
+        Ok(digest.iter().map(|byte| format!("{:02x}", byte)).collect())
+    }
+
+    /// The transaction that applies the migration and records it in [`MIGRATIONS_TABLE`].
+    fn transaction(&self) -> std::result::Result<Transaction, QueryBuildError> {
+        let checksum = self.checksum()?;
+
+        let mut bindings = Bindings::default();
+        let record = format!(
+            "CREATE {} CONTENT {{ version: {}, name: {}, checksum: {}, applied_at: time::now() }}",
+            bindings.bind(record(self.version)),
+            bindings.bind(self.version),
+            bindings.bind(self.name.as_str()),
+            bindings.bind(checksum),
+        );
+
+        Ok(self
+            .statements
+            .iter()
+            .cloned()
+            .fold(Transaction::new(), Transaction::add_statement)
+            .add_statement(BoundQuery::new(record, bindings)))
+    }
+}
+
+/// A migration recorded in [`MIGRATIONS_TABLE`].
+#[derive(Clone, Debug, PartialEq, serde::Deserialize)]
+pub struct AppliedMigration {
+    pub version: u64,
+    pub name: String,
+    pub checksum: String,
+    pub applied_at: Datetime,
+}
+
+/// Whether a migration was applied.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum MigrationState {
+    Pending,
+    Applied,
+    /// Applied, but changed since.
+    Modified,
+    /// Applied, but no longer defined.
+    Missing,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct MigrationStatus {
+    pub version: u64,
+    pub name: String,
+    pub state: MigrationState,
+    pub applied_at: Option<Datetime>,
+}
+
+/// The pending migrations a [`Migrator`] would apply, in order.
+///
+/// Its [`Display`] output is the SurrealQL that would be run, for dry runs.
+#[derive(Clone, Debug, Default)]
+pub struct MigrationPlan {
+    migrations: Vec<Migration>,
+}
+
+impl MigrationPlan {
+    pub fn migrations(&self) -> &[Migration] {
+        &self.migrations
+    }
+
+    pub fn versions(&self) -> Vec<u64> {
+        self.migrations.iter().map(Migration::version).collect()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.migrations.is_empty()
+    }
+
+    /// The SurrealQL that applies the plan, one transaction per migration.
+    pub fn surql(&self) -> std::result::Result<String, QueryBuildError> {
+        self.migrations
+            .iter()
+            .map(|migration| {
+                Ok(format!(
+                    "-- {} {}\n{}",
+                    migration.version,
+                    migration.name,
+                    migration.transaction()?.try_construct()?.inline()
+                ))
+            })
+            .collect::<std::result::Result<Vec<_>, QueryBuildError>>()
+            .map(|migrations| migrations.join("\n\n"))
+    }
+
+    /// Applies the migrations in order, each in its own transaction, stopping at the first
+    /// that fails.
+    pub async fn apply<E>(self, db: &E) -> Result<Vec<u64>>
+    where
+        E: Executor + ?Sized,
+    {
+        let mut applied = Vec::with_capacity(self.migrations.len());
+
+        for migration in self.migrations {
+            let failed = |source: DatabaseError| MigrationError::Failed {
+                version: migration.version,
+                name: migration.name.clone(),
+                source: Box::new(source),
+            };
+
+            let transaction = migration
+                .transaction()
+                .map_err(|err| DatabaseError::from(failed(err.into())))?;
+
+            transaction
+                .send(db)
+                .await
+                .map_err(|err| DatabaseError::from(failed(err)))?;
+
+            info!("Applied migration {} {}", migration.version, migration.name);
+            applied.push(migration.version);
+        }
+
+        Ok(applied)
+    }
+}
+
+impl Display for MigrationPlan {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self.surql() {
+            Ok(surql) => f.write_str(&surql),
+            Err(err) => write!(f, "-- {}", err),
+        }
+    }
+}
+
+/// Applies [`Migration`]s in version order and records them in [`MIGRATIONS_TABLE`].
+///
+/// Before planning, every applied migration is checked against its definition, so migrations
+/// that were changed or removed after being applied are reported instead of being skipped
+/// silently.
+///
+/// The database methods take any [`Executor`], like an `Arc<Surreal<C>>` or a
+/// `Lazy<Surreal<C>>`.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use std::sync::Arc;
+/// # use surrealdb::engine::local::Db;
+/// # use surrealdb::Surreal;
+/// # use surrealdb_abstraction::db::{DefineTable, Migration, Migrator};
+/// # async fn example(db: Arc<Surreal<Db>>) -> surrealdb_abstraction::Result<()> {
+/// let migrator = Migrator::new()
+///     .add_migration(
+///         Migration::new(1, "create_users").statement(DefineTable::new("user").schemafull()),
+///     )
+///     .add_migration(Migration::from_surql(
+///         2,
+///         "create_posts",
+///         "DEFINE TABLE post SCHEMALESS;",
+///     ));
+///
+/// println!("{}", migrator.plan_up(&db).await?);
+///
+/// let applied = migrator.migrate_up(&db).await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct Migrator {
+    migrations: Vec<Migration>,
+}
+
+impl Migrator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add_migration(mut self, migration: Migration) -> Self {
+        self.migrations.push(migration);
+
+        self
+    }
+
+    /// The migrations recorded in [`MIGRATIONS_TABLE`], by version.
+    pub async fn applied<E>(&self, db: &E) -> Result<Vec<AppliedMigration>>
+    where
+        E: Executor + ?Sized,
+    {
+        fetch_applied(db).await.map_err(Into::into)
+    }
+
+    /// The state of every defined and applied migration, by version.
+    pub async fn status<E>(&self, db: &E) -> Result<Vec<MigrationStatus>>
+    where
+        E: Executor + ?Sized,
+    {
+        let migrations = self.sorted()?;
+        let applied = self.applied(db).await?;
+
+        let mut status = migrations
+            .iter()
+            .map(|migration| {
+                let applied = applied
+                    .iter()
+                    .find(|applied| applied.version == migration.version);
+
+                let state = match applied {
+                    None => MigrationState::Pending,
+                    Some(applied) if applied.checksum == migration.checksum()? => {
+                        MigrationState::Applied
+                    }
+                    Some(_) => MigrationState::Modified,
+                };
+
+                Ok(MigrationStatus {
+                    version: migration.version,
+                    name: migration.name.clone(),
+                    state,
+                    applied_at: applied.map(|applied| applied.applied_at.clone()),
+                })
+            })
+            .collect::<InternalResult<Vec<_>>>()?;
+
+        status.extend(
+            applied
+                .into_iter()
+                .filter(|applied| !migrations.iter().any(|m| m.version == applied.version))
+                .map(|applied| MigrationStatus {
+                    version: applied.version,
+                    name: applied.name,
+                    state: MigrationState::Missing,
+                    applied_at: Some(applied.applied_at),
+                }),
+        );
+        status.sort_by_key(|status| status.version);
+
+        Ok(status)
+    }
+
+    /// Checks that every applied migration is still defined and unchanged.
+    pub async fn verify<E>(&self, db: &E) -> Result<()>
+    where
+        E: Executor + ?Sized,
+    {
+        let migrations = self.sorted()?;
+        let applied = self.applied(db).await?;
+
+        verify(&migrations, &applied).map_err(Into::into)
+    }
+
+    /// Records the current checksum of every applied migration that was changed since, returning
+    /// their versions.
+    ///
+    /// Only for changes that do not alter what a migration does, like a builder migration
+    /// rendered differently by a newer version of this crate, as the changed migration is not
+    /// run again.
+    pub async fn repair<E>(&self, db: &E) -> Result<Vec<u64>>
+    where
+        E: Executor + ?Sized,
+    {
+        self.repair_internal(db).await.map_err(Into::into)
+    }
+
+    async fn repair_internal<E>(&self, db: &E) -> InternalResult<Vec<u64>>
+    where
+        E: Executor + ?Sized,
+    {
+        let migrations = self.sorted()?;
+        let applied = fetch_applied(db).await?;
+
+        let mut repaired = Vec::new();
+        let mut transaction = Transaction::new();
+
+        for applied in applied {
+            let Some(migration) = migrations
+                .iter()
+                .find(|migration| migration.version == applied.version)
+            else {
+                continue;
+            };
+
+            let checksum = migration.checksum()?;
+
+            if checksum != applied.checksum {
+                let mut bindings = Bindings::default();
+                let query = format!(
+                    "UPDATE {} SET checksum = {}",
+                    bindings.bind(record(applied.version)),
+                    bindings.bind(checksum)
+                );
+
+                transaction = transaction.add_statement(BoundQuery::new(query, bindings));
+                repaired.push(applied.version);
+            }
+        }
+
+        if !transaction.is_empty() {
+            transaction.send(db).await?;
+            info!("Repaired the checksums of migrations {:?}", repaired);
+        }
+
+        Ok(repaired)
+    }
+
+    /// Plans every pending migration.
+    pub async fn plan_up<E>(&self, db: &E) -> Result<MigrationPlan>
+    where
+        E: Executor + ?Sized,
+    {
+        self.plan(db, None).await.map_err(Into::into)
+    }
+
+    /// Plans the pending migrations up to and including `version`.
+    pub async fn plan_to<E>(&self, db: &E, version: u64) -> Result<MigrationPlan>
+    where
+        E: Executor + ?Sized,
+    {
+        self.plan(db, Some(version)).await.map_err(Into::into)
+    }
+
+    /// Applies every pending migration, returning the versions that were applied.
+    pub async fn migrate_up<E>(&self, db: &E) -> Result<Vec<u64>>
+    where
+        E: Executor + ?Sized,
+    {
+        self.plan_up(db).await?.apply(db).await
+    }
+
+    /// Applies the pending migrations up to and including `version`, returning the versions
+    /// that were applied. Migrations cannot be reverted, so `version` must not be older than
+    /// the latest applied migration.
+    pub async fn migrate_to<E>(&self, db: &E, version: u64) -> Result<Vec<u64>>
+    where
+        E: Executor + ?Sized,
+    {
+        self.plan_to(db, version).await?.apply(db).await
+    }
+
+    async fn plan<E>(&self, db: &E, target: Option<u64>) -> InternalResult<MigrationPlan>
+    where
+        E: Executor + ?Sized,
+    {
+        let migrations = self.sorted()?;
+
+        if let Some(target) = target {
+            if !migrations
+                .iter()
+                .any(|migration| migration.version == target)
+            {
+                return Err(MigrationError::UnknownVersion(target).into());
+            }
+        }
+
+        let applied = fetch_applied(db).await?;
+        verify(&migrations, &applied)?;
+
+        let latest = applied.iter().map(|applied| applied.version).max();
+
+        if let (Some(target), Some(latest)) = (target, latest) {
+            if target < latest {
+                return Err(MigrationError::CannotMigrateDown { target, latest }.into());
+            }
+        }
+
+        let pending = migrations
+            .into_iter()
+            .filter(|migration| !applied.iter().any(|a| a.version == migration.version))
+            .filter(|migration| target.is_none_or(|target| migration.version <= target))
+            .collect::<Vec<_>>();
+
+        if let (Some(migration), Some(latest)) = (pending.first(), latest) {
+            if migration.version < latest {
+                return Err(MigrationError::OutOfOrder {
+                    version: migration.version,
+                    latest,
+                }
+                .into());
+            }
+        }
+
+        Ok(MigrationPlan {
+            migrations: pending,
+        })
+    }
+
+    /// The migrations by version, failing if a version is defined twice.
+    fn sorted(&self) -> InternalResult<Vec<Migration>> {
+        let mut migrations = self.migrations.clone();
+        migrations.sort_by_key(Migration::version);
+
+        if let Some(pair) = migrations
+            .windows(2)
+            .find(|pair| pair[0].version == pair[1].version)
+        {
+            return Err(MigrationError::DuplicateVersion(pair[0].version).into());
+        }
+
+        Ok(migrations)
+    }
+}
+
+/// The record `version` is recorded as applied in.
+fn record(version: u64) -> Thing {
+    Thing::from((MIGRATIONS_TABLE, Id::from(version)))
+}
+
+async fn fetch_applied<E>(db: &E) -> InternalResult<Vec<AppliedMigration>>
+where
+    E: Executor + ?Sized,
+{
+    let query = Select::query(MIGRATIONS_TABLE).order_by([OrderBy::asc("version")]);
+
+    Runner::send(db, query)
+        .await?
+        .take(0)
+        .map_err(DatabaseError::from)
+}
+
+/// Fails if an applied migration is no longer defined or was changed since.
+fn verify(migrations: &[Migration], applied: &[AppliedMigration]) -> InternalResult<()> {
+    for applied in applied {
+        let Some(migration) = migrations
+            .iter()
+            .find(|migration| migration.version == applied.version)
+        else {
+            return Err(MigrationError::MissingMigration {
+                version: applied.version,
+                name: applied.name.clone(),
+            }
+            .into());
+        };
+
+        let checksum = migration.checksum()?;
+
+        if checksum != applied.checksum {
+            return Err(MigrationError::ChecksumMismatch {
+                version: applied.version,
+                name: migration.name.clone(),
+                applied: applied.checksum.clone(),
+                current: checksum,
+            }
+            .into());
+        }
+    }
+
+    Ok(())
+}
@@ -1,13 +1,35 @@
 #![allow(dead_code)]
 #![allow(unused_macro_rules)]
 
+pub use batch::*;
 pub use error::*;
+pub use executor::*;
 pub use filter::*;
+pub use metrics::*;
+pub use migration::*;
+pub use observer::*;
 pub use query::*;
+pub use repository::*;
+pub use retry::*;
+pub use schema::*;
+#[cfg(feature = "derive")]
+pub use surrealdb_abstraction_derive::SurrealTable;
+pub use table::*;
+pub use transaction::*;
 // pub use runner::*;
 
+pub mod batch;
 pub mod error;
+pub mod executor;
 pub mod filter;
+pub mod metrics;
+pub mod migration;
+pub mod observer;
 pub mod query;
+pub mod repository;
+pub mod retry;
 pub mod runner;
+pub mod schema;
+pub mod table;
 mod test;
+pub mod transaction;
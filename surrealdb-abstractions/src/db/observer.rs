@@ -0,0 +1,243 @@
+use crate::db::{BoundQuery, DatabaseError, Executor, RetryPolicy};
+use std::sync::Arc;
+use std::time::Duration;
+use surrealdb::Surreal;
+
+/// A query about to be sent, as reported to a [`QueryObserver`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct QueryInfo {
+    operation: String,
+    table: Option<String>,
+    text: Option<String>,
+}
+
+impl QueryInfo {
+    pub(crate) fn new(query: &BoundQuery, table: Option<String>) -> Self {
+        Self {
+            operation: operation(&query.query),
+            table,
+            text: (!query.bindings.has_inlined()).then(|| query.query.trim().to_string()),
+        }
+    }
+
+    /// Labels several statements sent together, e.g. `BATCH` or `TRANSACTION`.
+    pub(crate) fn with_operation(mut self, operation: &str) -> Self {
+        self.operation = operation.to_string();
+
+        self
+    }
+
+    /// The statement, e.g. `SELECT` or `DEFINE TABLE`, or `BATCH` and `TRANSACTION` for several
+    /// statements.
+    pub fn operation(&self) -> &str {
+        &self.operation
+    }
+
+    /// The table the statement runs on, if it was built for a single table.
+    pub fn table(&self) -> Option<&str> {
+        self.table.as_deref()
+    }
+
+    /// The query text, with values bound as parameters replaced by their placeholder.
+    ///
+    /// `None` if values are written into the text, as in `DEFINE` statements and `LIVE SELECT`
+    /// conditions, or a batch or transaction containing one, so they are never logged.
+    pub fn text(&self) -> Option<&str> {
+        self.text.as_deref()
+    }
+
+    /// Opens the span of the query, with the field names of the OpenTelemetry semantic
+    /// conventions for database client spans.
+    #[cfg(feature = "tracing")]
+    pub(crate) fn span(&self) -> tracing::Span {
+        let name = match &self.table {
+            Some(table) => format!("{} {}", self.operation, table),
+            None => self.operation.clone(),
+        };
+
+        tracing::info_span!(
+            "db.query",
+            otel.name = %name,
+            otel.kind = "client",
+            otel.status_code = tracing::field::Empty,
+            db.system = "surrealdb",
+            db.operation.name = %self.operation,
+            db.collection.name = self.table.as_deref(),
+            db.query.text = self.text.as_deref(),
+            db.response.returned_rows = tracing::field::Empty,
+            "error.type" = tracing::field::Empty,
+        )
+    }
+}
+
+/// The statement keywords of `query`.
+fn operation(query: &str) -> String {
+    let mut words = query.split_whitespace();
+    let first = words.next().unwrap_or_default().to_uppercase();
+
+    match first.as_str() {
+        "LIVE" | "DEFINE" | "REMOVE" | "INFO" => match words.next() {
+            Some(second) => format!("{} {}", first, second.to_uppercase()),
+            None => first,
+        },
+        _ => first,
+    }
+}
+
+/// Hooks called around every query, e.g. for slow query logging or latency metrics.
+///
+/// Register it for a connection with [`Observed`]. Every attempt of a retried query is reported
+/// on its own.
+///
+/// # Examples
+///
+/// ```
+/// # use std::time::Duration;
+/// # use surrealdb_abstraction::db::{QueryInfo, QueryObserver};
+/// struct SlowQueries {
+///     threshold: Duration,
+/// }
+///
+/// impl QueryObserver for SlowQueries {
+///     fn after_execute(&self, query: &QueryInfo, duration: Duration, _rows: Option<u64>) {
+///         if duration > self.threshold {
+///             let text = query.text().unwrap_or_else(|| query.operation());
+///             println!("slow query ({:?}): {}", duration, text);
+///         }
+///     }
+/// }
+/// ```
+pub trait QueryObserver: Send + Sync {
+    fn before_execute(&self, query: &QueryInfo) {
+        let _ = query;
+    }
+
+    /// Called when the query succeeded. `rows` is the number of records returned: the length of
+    /// an array, otherwise one unless it is `NONE` or `NULL`, summed over the statements of a
+    /// batch or transaction.
+    fn after_execute(&self, query: &QueryInfo, duration: Duration, rows: Option<u64>) {
+        let _ = (query, duration, rows);
+    }
+
+    fn on_error(&self, query: &QueryInfo, duration: Duration, error: &DatabaseError) {
+        let _ = (query, duration, error);
+    }
+}
+
+impl<O> QueryObserver for Arc<O>
+where
+    O: QueryObserver + ?Sized,
+{
+    fn before_execute(&self, query: &QueryInfo) {
+        (**self).before_execute(query)
+    }
+
+    fn after_execute(&self, query: &QueryInfo, duration: Duration, rows: Option<u64>) {
+        (**self).after_execute(query, duration, rows)
+    }
+
+    fn on_error(&self, query: &QueryInfo, duration: Duration, error: &DatabaseError) {
+        (**self).on_error(query, duration, error)
+    }
+}
+
+/// Reports to every observer in order.
+impl<O> QueryObserver for Vec<O>
+where
+    O: QueryObserver,
+{
+    fn before_execute(&self, query: &QueryInfo) {
+        self.iter()
+            .for_each(|observer| observer.before_execute(query))
+    }
+
+    fn after_execute(&self, query: &QueryInfo, duration: Duration, rows: Option<u64>) {
+        self.iter()
+            .for_each(|observer| observer.after_execute(query, duration, rows))
+    }
+
+    fn on_error(&self, query: &QueryInfo, duration: Duration, error: &DatabaseError) {
+        self.iter()
+            .for_each(|observer| observer.on_error(query, duration, error))
+    }
+}
+
+/// The number of records in a raw result: the length of an array, otherwise one record unless
+/// it is `NONE` or `NULL`.
+pub(crate) fn returned_rows(value: &surrealdb::Value) -> Option<u64> {
+    let rows = match value.into_inner_ref() {
+        surrealdb::sql::Value::Array(rows) => rows.len(),
+        surrealdb::sql::Value::None | surrealdb::sql::Value::Null => 0,
+        _ => 1,
+    };
+
+    Some(rows as u64)
+}
+
+/// The number of records in the raw results of several statements.
+pub(crate) fn total_rows(values: &[surrealdb::Value]) -> Option<u64> {
+    values.iter().map(returned_rows).sum()
+}
+
+/// An [`Executor`] that reports every query run on it to a [`QueryObserver`].
+///
+/// # Examples
+///
+/// ```no_run
+/// # use surrealdb::engine::local::Db;
+/// # use surrealdb::Surreal;
+/// # use surrealdb_abstraction::db::{Observed, QueryInfo, QueryObserver, Select, Statement};
+/// # #[derive(serde::Deserialize)]
+/// # struct User { name: String }
+/// # struct SlowQueries;
+/// # impl QueryObserver for SlowQueries {}
+/// # async fn example(db: Surreal<Db>) -> surrealdb_abstraction::Result<()> {
+/// let db = Observed::new(db, SlowQueries);
+///
+/// let users: Vec<User> = Select::query("user").run(&db, 0).await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct Observed<E, O> {
+    executor: E,
+    observer: O,
+}
+
+impl<E, O> Observed<E, O> {
+    pub fn new(executor: E, observer: O) -> Self {
+        Self { executor, observer }
+    }
+
+    pub fn observer(&self) -> &O {
+        &self.observer
+    }
+
+    pub fn into_inner(self) -> E {
+        self.executor
+    }
+}
+
+impl<E, O> Executor for Observed<E, O>
+where
+    E: Executor,
+    O: QueryObserver,
+{
+    type Connection = E::Connection;
+
+    fn connection(&self) -> &Surreal<Self::Connection> {
+        self.executor.connection()
+    }
+
+    fn prepare(&self, query: BoundQuery) -> BoundQuery {
+        self.executor.prepare(query)
+    }
+
+    fn retry_policy(&self) -> Option<&RetryPolicy> {
+        self.executor.retry_policy()
+    }
+
+    fn observer(&self) -> Option<&dyn QueryObserver> {
+        Some(&self.observer)
+    }
+}
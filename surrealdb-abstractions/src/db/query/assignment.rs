@@ -0,0 +1,87 @@
+use crate::db::{Bindings, FieldPath, QueryBuildError};
+use std::fmt::{Display, Formatter};
+use surrealdb::sql::Value;
+
+/// The operator used in a field assignment, e.g. `SET age += 1`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AssignOperator {
+    /// `=`
+    Set,
+    /// `+=`
+    Increment,
+    /// `-=`
+    Decrement,
+    /// `+?=`
+    Extend,
+}
+
+impl Display for AssignOperator {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AssignOperator::Set => f.write_str("="),
+            AssignOperator::Increment => f.write_str("+="),
+            AssignOperator::Decrement => f.write_str("-="),
+            AssignOperator::Extend => f.write_str("+?="),
+        }
+    }
+}
+
+/// A single field assignment, as used by `ON DUPLICATE KEY UPDATE` and `SET` clauses.
+#[derive(Clone, Debug)]
+pub struct Assignment {
+    pub field: FieldPath,
+    pub operator: AssignOperator,
+    pub value: Value,
+}
+
+impl Assignment {
+    pub fn new<T>(field: impl Into<FieldPath>, operator: AssignOperator, value: T) -> Self
+    where
+        Value: From<T>,
+    {
+        Self {
+            field: field.into(),
+            operator,
+            value: Value::from(value),
+        }
+    }
+
+    pub fn set<T>(field: impl Into<FieldPath>, value: T) -> Self
+    where
+        Value: From<T>,
+    {
+        Self::new(field, AssignOperator::Set, value)
+    }
+
+    pub fn increment<T>(field: impl Into<FieldPath>, value: T) -> Self
+    where
+        Value: From<T>,
+    {
+        Self::new(field, AssignOperator::Increment, value)
+    }
+
+    pub fn decrement<T>(field: impl Into<FieldPath>, value: T) -> Self
+    where
+        Value: From<T>,
+    {
+        Self::new(field, AssignOperator::Decrement, value)
+    }
+
+    /// Renders the assignment with its value bound as a placeholder.
+    pub fn construct_with(self, bindings: &mut Bindings) -> Result<String, QueryBuildError> {
+        self.field.validate()?;
+
+        Ok(format!(
+            "{} {} {}",
+            self.field,
+            self.operator,
+            bindings.bind(self.value)
+        ))
+    }
+}
+
+impl Display for Assignment {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {} {}", self.field, self.operator, self.value)
+    }
+}
@@ -0,0 +1,235 @@
+use crate::db::QueryBuildError;
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+use surrealdb::sql::{Object, Value};
+
+/// Values bound to the `$p0`, `$p1`, ... placeholders of a constructed query.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Bindings(BTreeMap<String, Value>, Inlined);
+
+/// Whether values were written into the query text instead of being bound, so the text must
+/// not be logged.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+struct Inlined(bool);
+
+impl Bindings {
+    /// Binds `value` to the next free placeholder and returns the placeholder, e.g. `$p0`.
+    pub fn bind<T>(&mut self, value: T) -> String
+    where
+        Value: From<T>,
+    {
+        let name = format!("p{}", self.0.len());
+        self.0.insert(name.clone(), Value::from(value));
+
+        format!("${}", name)
+    }
+
+    /// Binds `value` to the named parameter `$name`, e.g. `tenant` for `$tenant`. Names of the
+    /// form `pN` are used for placeholders and must not be inserted.
+    pub fn insert(&mut self, name: impl Into<String>, value: impl Into<Value>) {
+        self.0.insert(name.into(), value.into());
+    }
+
+    /// Returns the value bound to `name` (without the leading `$`).
+    pub fn get(&self, name: &str) -> Option<&Value> {
+        self.0.get(name)
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&String, &Value)> {
+        self.0.iter()
+    }
+
+    pub fn into_inner(self) -> BTreeMap<String, Value> {
+        self.0
+    }
+
+    /// Records that values were written into the query text, like the clauses of a `DEFINE`
+    /// statement.
+    pub(crate) fn mark_inlined(&mut self) {
+        self.1 = Inlined(true);
+    }
+
+    /// Whether the query text contains values, see [`BoundQuery::inline`].
+    pub(crate) fn has_inlined(&self) -> bool {
+        self.1 .0
+    }
+
+    /// Moves the bindings of `query` into these bindings and returns its query text with every
+    /// placeholder renamed to the new one, so several queries can be sent together.
+    ///
+    /// Placeholders inside strings and escaped identifiers are left alone, as are parameters
+    /// that are not bound by `query`, like `$before`. Fails if `query` uses a `$pN` name it does
+    /// not bind, as that name may be bound to another query's value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use surrealdb_abstraction::db::{Bindings, BoundQuery, QueryBuildError};
+    ///
+    /// let mut first = Bindings::default();
+    /// first.bind(1);
+    /// let mut bindings = Bindings::default();
+    /// bindings.bind(0);
+    ///
+    /// let query = bindings.merge(BoundQuery::new("RETURN $p0 + $before".to_string(), first));
+    ///
+    /// assert_eq!(query, Ok("RETURN $p1 + $before".to_string()));
+    /// assert_eq!(bindings.len(), 2);
+    ///
+    /// let raw = BoundQuery::new("LET $p0 = 5".to_string(), Bindings::default());
+    ///
+    /// assert_eq!(
+    ///     bindings.merge(raw),
+    ///     Err(QueryBuildError::ReservedParameter("p0".to_string()))
+    /// );
+    /// ```
+    pub fn merge(&mut self, query: BoundQuery) -> Result<String, QueryBuildError> {
+        let BoundQuery {
+            query,
+            bindings: Bindings(mut values, inlined),
+        } = query;
+
+        if inlined.0 {
+            self.mark_inlined();
+        }
+
+        let mut renamed = BTreeMap::new();
+        let mut reserved = None;
+
+        let query = replace_placeholders(&query, |name| match values.remove(name) {
+            Some(value) => {
+                let placeholder = self.bind(value);
+                renamed.insert(name.to_string(), placeholder.clone());
+                Some(placeholder)
+            }
+            None => match renamed.get(name) {
+                Some(placeholder) => Some(placeholder.clone()),
+                None => {
+                    if is_placeholder(name) {
+                        reserved.get_or_insert_with(|| name.to_string());
+                    }
+
+                    None
+                }
+            },
+        });
+
+        match reserved {
+            Some(name) => Err(QueryBuildError::ReservedParameter(name)),
+            None => Ok(query),
+        }
+    }
+}
+
+/// Whether `name` has the form of the placeholders [`Bindings::bind`] creates.
+fn is_placeholder(name: &str) -> bool {
+    name.strip_prefix('p')
+        .is_some_and(|index| !index.is_empty() && index.bytes().all(|b| b.is_ascii_digit()))
+}
+
+/// Replaces every `$name` placeholder outside strings and escaped identifiers with the text
+/// `replace` returns for `name`, leaving it as is when that is `None`.
+fn replace_placeholders(query: &str, mut replace: impl FnMut(&str) -> Option<String>) -> String {
+    let mut replaced = String::with_capacity(query.len());
+    let mut quote = None;
+    let mut chars = query.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match quote {
+            Some(_) if c == '\\' => {
+                replaced.push(c);
+                replaced.extend(chars.next());
+                continue;
+            }
+            Some(end) if c == end => quote = None,
+            Some(_) => {}
+            None => match c {
+                '`' | '"' | '\'' => quote = Some(c),
+                '⟨' => quote = Some('⟩'),
+                '$' => {
+                    let mut name = String::new();
+
+                    while let Some(&c) = chars.peek().filter(|c| c.is_alphanumeric() || **c == '_')
+                    {
+                        name.push(c);
+                        chars.next();
+                    }
+
+                    match replace(&name) {
+                        Some(replacement) => replaced.push_str(&replacement),
+                        None => {
+                            replaced.push('$');
+                            replaced.push_str(&name);
+                        }
+                    }
+
+                    continue;
+                }
+                _ => {}
+            },
+        }
+
+        replaced.push(c);
+    }
+
+    replaced
+}
+
+impl From<Bindings> for Object {
+    fn from(bindings: Bindings) -> Self {
+        Object::from(bindings.0)
+    }
+}
+
+/// A constructed query together with the values bound to its placeholders.
+///
+/// The query text never contains the bound values, so it is safe to log and can be cached by
+/// its shape.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct BoundQuery {
+    pub query: String,
+    pub bindings: Bindings,
+}
+
+impl BoundQuery {
+    pub fn new(query: String, bindings: Bindings) -> Self {
+        Self { query, bindings }
+    }
+
+    /// Returns the query text with every bound value written in place of its placeholder.
+    ///
+    /// Only needed where SurrealDB stores the text instead of running it, like the clauses of
+    /// a `DEFINE` or `LIVE SELECT` statement, as parameters are not available when it is
+    /// evaluated later.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use surrealdb_abstraction::db::{Bindings, BoundQuery};
+    ///
+    /// let mut bindings = Bindings::default();
+    /// let placeholder = bindings.bind("it's");
+    /// let query = BoundQuery::new(format!("$value = {} OR $p9", placeholder), bindings);
+    ///
+    /// assert_eq!(query.inline(), "$value = \"it's\" OR $p9");
+    /// ```
+    pub fn inline(self) -> String {
+        let BoundQuery { query, bindings } = self;
+
+        replace_placeholders(&query, |name| bindings.get(name).map(ToString::to_string))
+    }
+}
+
+impl Display for BoundQuery {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.query)
+    }
+}
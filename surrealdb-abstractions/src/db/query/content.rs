@@ -0,0 +1,48 @@
+/// How the content of a statement is applied to a record.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ContentType {
+    /// `CONTENT`, replaces the record with the content.
+    Content,
+    /// `MERGE`, sets the fields in the content and keeps the others.
+    Merge,
+    /// `REPLACE`, replaces the record with the content, failing if it changes the id.
+    Replace,
+}
+
+/// Which fields [`set_content_from`](crate::db::QueryBuilder) keeps when serializing a struct
+/// into content.
+///
+/// # Examples
+///
+/// ```
+/// # use surrealdb_abstraction::db::ContentOptions;
+///
+/// let options = ContentOptions::new().skip_none().skip_id();
+///
+/// assert!(options.skip_none && options.skip_id);
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ContentOptions {
+    /// Leave out fields that serialize to `NONE`, e.g. `Option::None`.
+    pub skip_none: bool,
+    /// Leave out the `id` field, so the record id is not part of the content.
+    pub skip_id: bool,
+}
+
+impl ContentOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn skip_none(mut self) -> Self {
+        self.skip_none = true;
+
+        self
+    }
+
+    pub fn skip_id(mut self) -> Self {
+        self.skip_id = true;
+
+        self
+    }
+}
@@ -1,14 +1,15 @@
+use crate::db::query::query_parts::{content, return_type, validate_table};
 use crate::db::query::traits::{Query, Statement};
 use crate::db::query::ContentType;
-use crate::db::QueryBuilder;
-use crate::prelude::query::query_parts::content;
-use crate::{impl_content, impl_only, impl_parallel, impl_timeout};
+use crate::db::{Bindings, QueryBuildError, QueryBuilder, Ready};
+use crate::{impl_content, impl_only, impl_parallel, impl_return, impl_target, impl_timeout};
 use log::debug;
 
 pub struct Create;
 
 impl Statement for Create {
     const STATEMENT: &'static str = "CREATE";
+    type State = Ready;
 }
 
 impl QueryBuilder<Create> {
@@ -16,10 +17,19 @@ impl QueryBuilder<Create> {
     impl_parallel!();
     impl_timeout!();
     impl_content!();
+    impl_return!();
+    impl_target!();
 }
 
 impl Query for QueryBuilder<Create> {
-    fn construct(self) -> String {
+    fn construct_with(mut self, bindings: &mut Bindings) -> Result<String, QueryBuildError> {
+        let target = match self.from.take() {
+            Some(from) => from.construct_with(bindings)?,
+            None => {
+                validate_table(&self.table)?;
+                self.table.to_string()
+            }
+        };
         let mut query = String::new();
         query.push_str(Self::STATEMENT);
 
@@ -27,9 +37,11 @@ impl Query for QueryBuilder<Create> {
             query.push_str(" ONLY");
         }
 
-        query.push_str(format!(" {}", self.table).as_str());
+        query.push_str(format!(" {}", target).as_str());
 
-        content(ContentType::Content, self.content, &mut query);
+        content(ContentType::Content, self.content, &mut query, bindings)?;
+
+        return_type(self.return_type, &mut query)?;
 
         if let Some((duration, unit)) = self.timeout {
             query.push_str(format!(" TIMEOUT {}{}", duration, unit).as_str());
@@ -41,6 +53,6 @@ impl Query for QueryBuilder<Create> {
 
         debug!("Constructed query: {}", query);
 
-        query
+        Ok(query)
     }
 }
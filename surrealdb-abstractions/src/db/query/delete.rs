@@ -1,22 +1,33 @@
+use crate::db::query::query_parts::{return_type, validate_table};
 use crate::db::query::traits::{Query, Statement};
-use crate::db::QueryBuilder;
-use crate::{impl_filter, impl_only, impl_parallel};
+use crate::db::{Bindings, QueryBuildError, QueryBuilder, Ready, Return};
+use crate::{impl_filter, impl_only, impl_parallel, impl_return, impl_target};
 use log::debug;
 
 pub struct Delete;
 
 impl Statement for Delete {
     const STATEMENT: &'static str = "DELETE";
+    type State = Ready;
 }
 
 impl QueryBuilder<Delete> {
     impl_filter!();
     impl_only!();
     impl_parallel!();
+    impl_return!();
+    impl_target!();
 }
 
 impl Query for QueryBuilder<Delete> {
-    fn construct(self) -> String {
+    fn construct_with(self, bindings: &mut Bindings) -> Result<String, QueryBuildError> {
+        let target = match self.from {
+            Some(from) => from.construct_with(bindings)?,
+            None => {
+                validate_table(&self.table)?;
+                self.table.to_string()
+            }
+        };
         let mut query = String::new();
         query.push_str(Self::STATEMENT);
         query.push(' ');
@@ -25,14 +36,18 @@ impl Query for QueryBuilder<Delete> {
             query.push_str("ONLY ");
         }
 
-        query.push_str(&self.table);
+        query.push_str(&target);
 
         query.push(' ');
-        query.push_str(&self.filter.construct());
+        query.push_str(&self.filter.construct_with(bindings)?);
 
-        if self.only {
-            query.push_str(" RETURN $before");
-        }
+        // A single deleted record is returned as it was, unless told otherwise
+        let returning = match self.return_type {
+            None if self.only => Some(Return::Before),
+            returning => returning,
+        };
+
+        return_type(returning, &mut query)?;
 
         if self.parallel {
             query.push_str(" PARALLEL");
@@ -40,6 +55,6 @@ impl Query for QueryBuilder<Delete> {
 
         debug!("Constructed query: {}", query);
 
-        query
+        Ok(query)
     }
 }
@@ -0,0 +1,284 @@
+use crate::db::{Bindings, FieldPath, Ident, Query, QueryBuildError, QueryBuilder, Select};
+use std::fmt::{Display, Formatter};
+use std::ops::{Bound, RangeBounds};
+use surrealdb::sql::{Id, IdRange, Thing};
+
+/// The direction of a step in a [`GraphPath`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum EdgeDirection {
+    /// `->`
+    Out,
+    /// `<-`
+    In,
+    /// `<->`
+    Both,
+}
+
+impl Display for EdgeDirection {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EdgeDirection::Out => f.write_str("->"),
+            EdgeDirection::In => f.write_str("<-"),
+            EdgeDirection::Both => f.write_str("<->"),
+        }
+    }
+}
+
+/// A graph traversal like `->likes->post<-wrote<-user`, optionally followed by a field of the
+/// records it ends at.
+///
+/// # Examples
+///
+/// ```
+/// # use surrealdb_abstraction::db::GraphPath;
+///
+/// let path = GraphPath::new()
+///     .out("likes")
+///     .out("post")
+///     .incoming("wrote")
+///     .incoming("user")
+///     .field("name");
+///
+/// assert_eq!(path.to_string(), "->likes->post<-wrote<-user.name");
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct GraphPath {
+    steps: Vec<(EdgeDirection, Ident)>,
+    field: Option<FieldPath>,
+}
+
+impl GraphPath {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a step to `table` in `direction`.
+    pub fn step(mut self, direction: EdgeDirection, table: impl Into<Ident>) -> Self {
+        self.steps.push((direction, table.into()));
+
+        self
+    }
+
+    /// `->table`
+    pub fn out(self, table: impl Into<Ident>) -> Self {
+        self.step(EdgeDirection::Out, table)
+    }
+
+    /// `<-table`
+    pub fn incoming(self, table: impl Into<Ident>) -> Self {
+        self.step(EdgeDirection::In, table)
+    }
+
+    /// `<->table`
+    pub fn both(self, table: impl Into<Ident>) -> Self {
+        self.step(EdgeDirection::Both, table)
+    }
+
+    /// Selects `field` of the records the path ends at, e.g. `->likes->post.title`.
+    pub fn field(mut self, field: impl Into<FieldPath>) -> Self {
+        self.field = Some(field.into());
+
+        self
+    }
+
+    pub fn validate(&self) -> Result<(), QueryBuildError> {
+        if self.steps.is_empty() {
+            return Err(QueryBuildError::EmptyGraphPath);
+        }
+
+        self.steps
+            .iter()
+            .try_for_each(|(_, table)| table.validate())?;
+
+        match &self.field {
+            Some(field) => field.validate(),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Display for GraphPath {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for (direction, table) in &self.steps {
+            write!(f, "{}{}", direction, table)?;
+        }
+
+        if let Some(field) = &self.field {
+            write!(f, ".{}", field)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// A selected field: a plain field, a graph traversal, or a subquery.
+#[derive(Clone, Debug)]
+pub enum Expression {
+    Field(FieldPath),
+    Graph(GraphPath),
+    Subquery(Box<QueryBuilder<Select>>),
+}
+
+impl Expression {
+    /// Renders the expression, binding the values of a subquery in `bindings`.
+    pub fn construct_with(self, bindings: &mut Bindings) -> Result<String, QueryBuildError> {
+        match self {
+            Expression::Field(field) => {
+                field.validate()?;
+                Ok(field.to_string())
+            }
+            Expression::Graph(path) => {
+                path.validate()?;
+                Ok(path.to_string())
+            }
+            Expression::Subquery(query) => subquery(*query, bindings),
+        }
+    }
+}
+
+impl From<FieldPath> for Expression {
+    fn from(field: FieldPath) -> Self {
+        Expression::Field(field)
+    }
+}
+
+impl From<&str> for Expression {
+    fn from(field: &str) -> Self {
+        Expression::Field(field.into())
+    }
+}
+
+impl From<GraphPath> for Expression {
+    fn from(path: GraphPath) -> Self {
+        Expression::Graph(path)
+    }
+}
+
+impl From<QueryBuilder<Select>> for Expression {
+    fn from(query: QueryBuilder<Select>) -> Self {
+        Expression::Subquery(Box::new(query))
+    }
+}
+
+/// What a `SELECT` reads from.
+///
+/// Record ids are bound like any other value, so they are never written into the query.
+///
+/// # Examples
+///
+/// ```
+/// # use surrealdb_abstraction::db::{GraphPath, Query, Select, Statement, Target};
+/// # use surrealdb::sql::Thing;
+///
+/// let query = Select::from(Target::range("person", 1..1000)).try_construct()?;
+/// assert_eq!(query.query, "SELECT * FROM $p0 ");
+///
+/// let query = Select::from(Target::graph(
+///         Thing::from(("person", "tobie")),
+///         GraphPath::new().out("likes").out("post"),
+///     ))
+///     .try_construct()?;
+/// assert_eq!(query.query, "SELECT * FROM $p0->likes->post ");
+/// # Ok::<_, surrealdb_abstraction::db::QueryBuildError>(())
+/// ```
+#[derive(Clone, Debug)]
+pub enum Target {
+    Table(Ident),
+    /// A single record, or a range of records.
+    Record(Thing),
+    /// The records reached by following `path` from a record.
+    Graph(Thing, GraphPath),
+    Subquery(Box<QueryBuilder<Select>>),
+}
+
+impl Target {
+    /// The records of `table` with ids in `range`, e.g. `person:1..1000`.
+    pub fn range<T>(table: impl Into<String>, range: impl RangeBounds<T>) -> Self
+    where
+        T: Clone,
+        Id: From<T>,
+    {
+        let bound = |bound: Bound<&T>| match bound {
+            Bound::Included(id) => Bound::Included(Id::from(id.clone())),
+            Bound::Excluded(id) => Bound::Excluded(Id::from(id.clone())),
+            Bound::Unbounded => Bound::Unbounded,
+        };
+
+        let range = IdRange {
+            beg: bound(range.start_bound()),
+            end: bound(range.end_bound()),
+        };
+
+        Target::Record(Thing::from((table.into(), Id::Range(Box::new(range)))))
+    }
+
+    pub fn graph(from: Thing, path: GraphPath) -> Self {
+        Target::Graph(from, path)
+    }
+
+    /// The table of the records read, `None` for a subquery or a graph traversal without steps.
+    pub fn table(&self) -> Option<&str> {
+        match self {
+            Target::Table(table) => Some(table.as_str()),
+            Target::Record(record) => Some(&record.tb),
+            Target::Graph(from, path) => match path.steps.last() {
+                Some((_, table)) => Some(table.as_str()),
+                None => Some(&from.tb),
+            },
+            Target::Subquery(_) => None,
+        }
+    }
+
+    /// Renders the target, binding record ids and subquery values in `bindings`.
+    pub fn construct_with(self, bindings: &mut Bindings) -> Result<String, QueryBuildError> {
+        match self {
+            Target::Table(table) => {
+                if table.is_empty() {
+                    return Err(QueryBuildError::EmptyTableName);
+                }
+
+                table.validate()?;
+                Ok(table.to_string())
+            }
+            Target::Record(record) => Ok(bindings.bind(record)),
+            Target::Graph(from, path) => {
+                path.validate()?;
+                Ok(format!("{}{}", bindings.bind(from), path))
+            }
+            Target::Subquery(query) => subquery(*query, bindings),
+        }
+    }
+}
+
+impl From<Ident> for Target {
+    fn from(table: Ident) -> Self {
+        Target::Table(table)
+    }
+}
+
+impl From<&str> for Target {
+    fn from(table: &str) -> Self {
+        Target::Table(table.into())
+    }
+}
+
+impl From<Thing> for Target {
+    fn from(record: Thing) -> Self {
+        Target::Record(record)
+    }
+}
+
+impl From<QueryBuilder<Select>> for Target {
+    fn from(query: QueryBuilder<Select>) -> Self {
+        Target::Subquery(Box::new(query))
+    }
+}
+
+fn subquery(
+    query: QueryBuilder<Select>,
+    bindings: &mut Bindings,
+) -> Result<String, QueryBuildError> {
+    let query = query.construct_with(bindings)?;
+
+    Ok(format!("({})", query.trim()))
+}
@@ -0,0 +1,236 @@
+use crate::db::QueryBuildError;
+use std::fmt::{Display, Formatter};
+
+/// A table or field name.
+///
+/// Names that are not plain SurrealQL identifiers (e.g. `my-table`, `1st`, or a reserved
+/// keyword like `select`) are escaped with backticks when rendered, so any name is written into
+/// the query as a single identifier.
+///
+/// # Examples
+///
+/// ```
+/// # use surrealdb_abstraction::db::Ident;
+///
+/// assert_eq!(Ident::from("user").to_string(), "user");
+/// assert_eq!(Ident::from("user-data").to_string(), "`user-data`");
+/// assert_eq!(Ident::from("a`b").to_string(), "`a\\`b`");
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Ident(String);
+
+impl Ident {
+    /// Creates an identifier, failing if it can never be valid (see [`Ident::validate`]).
+    pub fn new(name: impl Into<String>) -> Result<Self, QueryBuildError> {
+        let ident = Self(name.into());
+        ident.validate()?;
+
+        Ok(ident)
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Checks that the name is not empty and contains no control characters, which cannot be
+    /// escaped.
+    pub fn validate(&self) -> Result<(), QueryBuildError> {
+        if self.0.is_empty() || self.0.chars().any(char::is_control) {
+            return Err(QueryBuildError::InvalidIdentifier(self.0.clone()));
+        }
+
+        Ok(())
+    }
+
+    /// Whether the name has to be escaped to be used as an identifier.
+    pub fn needs_escaping(&self) -> bool {
+        let mut chars = self.0.chars();
+
+        let plain = match chars.next() {
+            Some(first) => {
+                (first.is_ascii_alphabetic() || first == '_')
+                    && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+            }
+            None => false,
+        };
+
+        !plain || surrealdb::syn::could_be_reserved_keyword(&self.0)
+    }
+}
+
+impl Display for Ident {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.needs_escaping() {
+            write!(f, "`{}`", self.0.replace('\\', "\\\\").replace('`', "\\`"))
+        } else {
+            f.write_str(&self.0)
+        }
+    }
+}
+
+impl From<&str> for Ident {
+    fn from(name: &str) -> Self {
+        Self(name.to_string())
+    }
+}
+
+impl From<String> for Ident {
+    fn from(name: String) -> Self {
+        Self(name)
+    }
+}
+
+impl From<&String> for Ident {
+    fn from(name: &String) -> Self {
+        Self(name.clone())
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+enum FieldPathInner {
+    Parts(Vec<Ident>),
+    Raw(String),
+    /// A string with parentheses, which looks like an expression but would be escaped as a
+    /// field. Rejected by [`FieldPath::validate`].
+    Expression(String),
+}
+
+/// A field, or a path into nested objects like `address.city`.
+///
+/// Every part of the path is escaped like an [`Ident`], except `*`. Converting from a string
+/// splits it on `.`. A string with parentheses, like `count()`, is rejected when the query is
+/// built, use [`FieldPath::raw`] or an [`Expression`](crate::db::Expression) for it.
+///
+/// # Examples
+///
+/// ```
+/// # use surrealdb_abstraction::db::FieldPath;
+///
+/// assert_eq!(FieldPath::from("address.city").to_string(), "address.city");
+/// assert_eq!(FieldPath::from("tags.*").to_string(), "tags.*");
+/// assert_eq!(FieldPath::from("first-name").to_string(), "`first-name`");
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct FieldPath(FieldPathInner);
+
+impl FieldPath {
+    pub fn new(parts: Vec<Ident>) -> Self {
+        Self(FieldPathInner::Parts(parts))
+    }
+
+    /// `*`
+    pub fn all() -> Self {
+        Self::from("*")
+    }
+
+    /// A field expression that is written into the query verbatim, e.g. `count()`.
+    ///
+    /// Never build this from user input, it is not escaped.
+    pub fn raw(expression: impl Into<String>) -> Self {
+        Self(FieldPathInner::Raw(expression.into()))
+    }
+
+    /// Appends `part` to the path.
+    pub fn join(self, part: impl Into<Ident>) -> Self {
+        match self.0 {
+            FieldPathInner::Parts(mut parts) => {
+                parts.push(part.into());
+                Self(FieldPathInner::Parts(parts))
+            }
+            FieldPathInner::Raw(raw) => {
+                Self(FieldPathInner::Raw(format!("{}.{}", raw, part.into())))
+            }
+            FieldPathInner::Expression(expression) => Self(FieldPathInner::Expression(format!(
+                "{}.{}",
+                expression,
+                part.into().as_str()
+            ))),
+        }
+    }
+
+    /// The parts of the path, `None` for a [raw](FieldPath::raw) expression.
+    pub fn parts(&self) -> Option<&[Ident]> {
+        match &self.0 {
+            FieldPathInner::Parts(parts) => Some(parts),
+            FieldPathInner::Raw(_) | FieldPathInner::Expression(_) => None,
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), QueryBuildError> {
+        match &self.0 {
+            FieldPathInner::Parts(parts) if parts.is_empty() => {
+                Err(QueryBuildError::InvalidIdentifier(String::new()))
+            }
+            FieldPathInner::Parts(parts) => parts.iter().try_for_each(Ident::validate),
+            FieldPathInner::Raw(_) => Ok(()),
+            FieldPathInner::Expression(expression) => {
+                Err(QueryBuildError::ExpressionAsField(expression.clone()))
+            }
+        }
+    }
+}
+
+impl Display for FieldPath {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match &self.0 {
+            FieldPathInner::Parts(parts) => {
+                for (i, part) in parts.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(".")?;
+                    }
+
+                    if part.as_str() == "*" {
+                        f.write_str("*")?;
+                    } else {
+                        write!(f, "{}", part)?;
+                    }
+                }
+
+                Ok(())
+            }
+            FieldPathInner::Raw(raw) => f.write_str(raw),
+            FieldPathInner::Expression(expression) => write!(f, "{}", Ident::from(expression)),
+        }
+    }
+}
+
+impl From<&str> for FieldPath {
+    fn from(path: &str) -> Self {
+        if path.contains(['(', ')']) {
+            return Self(FieldPathInner::Expression(path.to_string()));
+        }
+
+        Self::new(path.split('.').map(Ident::from).collect())
+    }
+}
+
+impl From<String> for FieldPath {
+    fn from(path: String) -> Self {
+        Self::from(path.as_str())
+    }
+}
+
+impl From<&String> for FieldPath {
+    fn from(path: &String) -> Self {
+        Self::from(path.as_str())
+    }
+}
+
+impl From<Ident> for FieldPath {
+    fn from(ident: Ident) -> Self {
+        Self::new(vec![ident])
+    }
+}
+
+/// Renders an object key, quoting it when it is not a plain identifier.
+pub(crate) fn escape_key(key: &str) -> String {
+    if !key.is_empty() && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+        key.to_string()
+    } else {
+        format!("\"{}\"", key.replace('\\', "\\\\").replace('"', "\\\""))
+    }
+}
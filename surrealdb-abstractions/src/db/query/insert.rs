@@ -1,15 +1,84 @@
+use crate::db::query::query_parts::{parallel, return_type, timeout, validate_table};
 use crate::db::query::traits::Statement;
-use crate::db::QueryBuilder;
+use crate::db::{Bindings, QueryBuildError, QueryBuilder, Ready};
 use crate::prelude::Query;
+use crate::{
+    impl_ignore, impl_insert_relation, impl_on_duplicate, impl_parallel, impl_records, impl_return,
+    impl_timeout,
+};
+use log::debug;
+use surrealdb::sql::{Object, Value};
 
 pub struct Insert;
 
 impl Statement for Insert {
     const STATEMENT: &'static str = "INSERT";
+    type State = Ready;
+}
+
+impl QueryBuilder<Insert> {
+    impl_records!();
+    impl_ignore!();
+    impl_insert_relation!();
+    impl_on_duplicate!();
+    impl_return!();
+    impl_timeout!();
+    impl_parallel!();
 }
 
 impl Query for QueryBuilder<Insert> {
-    fn construct(self) -> String {
-        todo!("This statement is not yet implemented!")
+    fn construct_with(self, bindings: &mut Bindings) -> Result<String, QueryBuildError> {
+        validate_table(&self.table)?;
+        let mut query = String::new();
+        query.push_str(Self::STATEMENT);
+
+        if self.insert_relation {
+            query.push_str(" RELATION");
+        }
+
+        if self.ignore {
+            query.push_str(" IGNORE");
+        }
+
+        query.push_str(format!(" INTO {}", self.table).as_str());
+
+        // A single record is inserted as an object, several as an array of objects
+        let Some(records) = self.records.filter(|records| !records.is_empty()) else {
+            return Err(QueryBuildError::MissingRecords {
+                statement: Self::STATEMENT,
+            });
+        };
+
+        let mut records = records
+            .into_iter()
+            .map(|record| Value::Object(Object::from(record)))
+            .collect::<Vec<_>>();
+
+        let data = if records.len() == 1 {
+            records.remove(0)
+        } else {
+            Value::from(records)
+        };
+
+        query.push_str(format!(" {}", bindings.bind(data)).as_str());
+
+        if let Some(on_duplicate) = self.on_duplicate {
+            let assignments = on_duplicate
+                .into_iter()
+                .map(|assignment| assignment.construct_with(bindings))
+                .collect::<Result<Vec<_>, _>>()?;
+
+            query.push_str(format!(" ON DUPLICATE KEY UPDATE {}", assignments.join(", ")).as_str());
+        }
+
+        return_type(self.return_type, &mut query)?;
+
+        timeout(self.timeout, &mut query);
+
+        parallel(self.parallel, &mut query);
+
+        debug!("Constructed query: {}", query);
+
+        Ok(query)
     }
 }
@@ -0,0 +1,233 @@
+use crate::db::error::DatabaseError;
+use crate::db::query::query_parts::{field_list, validate_table};
+use crate::db::query::traits::{Query, Statement};
+use crate::db::runner::Runner;
+use crate::db::{Bindings, BoundQuery, Executor, QueryBuildError, QueryBuilder, QueryInfo, Ready};
+use crate::{impl_diff, impl_fetch_fields, impl_fields, impl_filter, Result};
+use futures::{Stream, StreamExt};
+use log::debug;
+use serde::de::DeserializeOwned;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use surrealdb::method::QueryStream;
+use surrealdb::Uuid;
+
+/// `LIVE SELECT`, which streams the changes to the records of a table as they happen.
+///
+/// Values in the condition are written into the query, as SurrealDB evaluates it whenever a
+/// record changes, long after the query's parameters are gone.
+///
+/// # Examples
+///
+/// ```
+/// # use surrealdb_abstraction::db::{Condition, Live, Query, Statement};
+///
+/// let query = Live::query("user")
+///     .add_field("name", None)
+///     .and_where(Condition::gte("age", 18))
+///     .try_construct()?;
+///
+/// assert_eq!(query.query, "LIVE SELECT name FROM user WHERE age >= 18");
+///
+/// let diff = Live::query("user").set_diff(true).try_construct()?;
+///
+/// assert_eq!(diff.query, "LIVE SELECT DIFF FROM user");
+/// # Ok::<_, surrealdb_abstraction::db::QueryBuildError>(())
+/// ```
+///
+/// Streaming the notifications:
+///
+/// ```no_run
+/// # use futures::TryStreamExt;
+/// # use surrealdb::engine::local::Db;
+/// # use surrealdb::Surreal;
+/// # use surrealdb_abstraction::db::{Live, LiveAction, Statement};
+/// # #[derive(serde::Deserialize)]
+/// # struct User { name: String }
+/// # async fn example(db: &Surreal<Db>) -> surrealdb_abstraction::Result<()> {
+/// let mut users = Live::query("user").stream::<_, User>(db).await?;
+///
+/// while let Some(notification) = users.try_next().await? {
+///     if notification.action == LiveAction::Delete {
+///         println!("{} was deleted", notification.data.name);
+///     }
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub struct Live;
+
+impl Statement for Live {
+    const STATEMENT: &'static str = "LIVE SELECT";
+    type State = Ready;
+}
+
+impl QueryBuilder<Live> {
+    impl_fields!();
+    impl_filter!();
+    impl_fetch_fields!();
+    impl_diff!();
+
+    /// Starts the live query on `db`, streaming a [`Notification`] for every change.
+    ///
+    /// With [`set_diff`](Self::set_diff) the data of a notification is a list of JSON Patch
+    /// operations, so deserialize it into a list of structs with `op`, `path` and `value`
+    /// fields. The live query is killed when the stream is dropped.
+    pub async fn stream<E, T>(self, db: &E) -> Result<LiveStream<T>>
+    where
+        E: Executor + ?Sized,
+        T: DeserializeOwned + Unpin,
+    {
+        let table = self.table.to_string();
+        let query = Runner::construct(self)?;
+        let info = QueryInfo::new(&query, Some(table));
+
+        let mut response = Runner::observe(db, &info, Runner::send(db, query), |_| None).await?;
+
+        match response.stream::<surrealdb::Notification<T>>(0) {
+            Ok(inner) => Ok(LiveStream { inner }),
+            Err(err) => Err(DatabaseError::from(err).into()),
+        }
+    }
+}
+
+impl Query for QueryBuilder<Live> {
+    /// Writes the values into the text instead of binding them.
+    fn construct_with(self, shared: &mut Bindings) -> std::result::Result<String, QueryBuildError> {
+        validate_table(&self.table)?;
+
+        let mut bindings = Bindings::default();
+        let mut query = String::new();
+
+        query.push_str(Self::STATEMENT);
+        query.push(' ');
+
+        let mut fields = self
+            .fields
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(field, alias)| {
+                field.validate()?;
+
+                match alias {
+                    Some(alias) => {
+                        alias.validate()?;
+                        Ok(format!("{} AS {}", field, alias))
+                    }
+                    None => Ok(field.to_string()),
+                }
+            })
+            .collect::<std::result::Result<Vec<_>, QueryBuildError>>()?;
+
+        for (expression, alias) in self.expressions.unwrap_or_default() {
+            let expression = expression.construct_with(&mut bindings)?;
+
+            match alias {
+                Some(alias) => {
+                    alias.validate()?;
+                    fields.push(format!("{} AS {}", expression, alias));
+                }
+                None => fields.push(expression),
+            }
+        }
+
+        match (self.diff, fields.is_empty()) {
+            (true, true) => query.push_str("DIFF"),
+            (true, false) => {
+                return Err(QueryBuildError::ConflictingProjection {
+                    statement: Self::STATEMENT,
+                })
+            }
+            (false, true) => query.push('*'),
+            (false, false) => query.push_str(&fields.join(", ")),
+        }
+
+        query.push_str(" FROM ");
+        query.push_str(&self.table.to_string());
+
+        let filter_clause = self.filter.construct_with(&mut bindings)?;
+        if !filter_clause.is_empty() {
+            query.push(' ');
+            query.push_str(&filter_clause);
+        }
+
+        if let Some(fetch_fields) = self.fetch_fields {
+            query.push_str(format!(" FETCH {}", field_list(&fetch_fields)?).as_str());
+        }
+
+        if !bindings.is_empty() {
+            shared.mark_inlined();
+        }
+
+        let query = BoundQuery::new(query, bindings).inline();
+
+        debug!("Constructed query: {}", query);
+
+        Ok(query)
+    }
+}
+
+/// What happened to the record of a [`Notification`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum LiveAction {
+    Create,
+    Update,
+    Delete,
+}
+
+/// A change to a record watched by a [`Live`] query.
+#[derive(Clone, Debug)]
+pub struct Notification<T> {
+    /// The id of the live query that sent the notification.
+    pub query_id: Uuid,
+    pub action: LiveAction,
+    /// The record after the change, or before it for [`LiveAction::Delete`].
+    pub data: T,
+}
+
+impl<T> TryFrom<surrealdb::Notification<T>> for Notification<T> {
+    type Error = DatabaseError;
+
+    fn try_from(
+        notification: surrealdb::Notification<T>,
+    ) -> std::result::Result<Self, Self::Error> {
+        let action = match notification.action {
+            surrealdb::Action::Create => LiveAction::Create,
+            surrealdb::Action::Update => LiveAction::Update,
+            surrealdb::Action::Delete => LiveAction::Delete,
+            action => {
+                return Err(anyhow::anyhow!("Unsupported live query action {:?}", action).into())
+            }
+        };
+
+        Ok(Notification {
+            query_id: notification.query_id,
+            action,
+            data: notification.data,
+        })
+    }
+}
+
+/// The notifications of a [`Live`] query. Dropping the stream kills the live query.
+#[must_use = "streams do nothing unless you poll them"]
+pub struct LiveStream<T> {
+    inner: QueryStream<surrealdb::Notification<T>>,
+}
+
+impl<T> Stream for LiveStream<T>
+where
+    T: DeserializeOwned + Unpin,
+{
+    type Item = Result<Notification<T>>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.inner.poll_next_unpin(cx).map(|notification| {
+            notification.map(|notification| {
+                notification
+                    .map_err(DatabaseError::from)
+                    .and_then(Notification::try_from)
+                    .map_err(Into::into)
+            })
+        })
+    }
+}
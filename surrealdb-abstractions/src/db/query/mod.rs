@@ -1,58 +1,101 @@
+pub mod assignment;
+pub mod bindings;
+pub mod content;
 pub mod create;
 pub mod delete;
+pub mod expression;
+pub mod ident;
 pub mod insert;
-mod query_parts;
+pub mod live;
+pub mod order;
+pub mod paginate;
+pub mod patch;
+pub(crate) mod query_parts;
 pub mod relate;
+pub mod returning;
 pub mod select;
+pub mod state;
 pub mod traits;
 pub mod update;
 pub mod upsert;
 
+pub use assignment::*;
+pub use bindings::*;
+pub use content::*;
 pub use create::*;
 pub use delete::*;
+pub use expression::*;
+pub use ident::*;
 pub use insert::*;
+pub use live::*;
+pub use order::*;
+pub use paginate::*;
+pub use patch::*;
 pub use relate::*;
+pub use returning::*;
 pub use select::*;
+pub use state::*;
 pub use traits::*;
 pub use update::*;
 pub use upsert::*;
 
-use super::filter::Filter;
+use super::error::QueryBuildError;
+use super::filter::{Condition, Filter};
+use super::retry::RetryPolicy;
 use std::collections::BTreeMap;
 use std::marker::PhantomData;
 use surrealdb::sql::{Thing, Value};
 
-enum ContentType {
-    Content,
-    Merge,
-    Patch,
-}
-
 #[derive(Clone, Debug)]
-pub struct QueryBuilder<Type>
+pub struct QueryBuilder<Type, State = Ready>
 where
     Type: Statement + ?Sized,
+    State: BuilderState,
 {
     /// Fields to return (field, alias)
-    fields: Option<BTreeMap<String, Option<String>>>,
+    fields: Option<BTreeMap<FieldPath, Option<Ident>>>,
+    /// Field expressions to return (expression, alias), after `fields`
+    expressions: Option<Vec<(Expression, Option<Ident>)>>,
     /// Fields to omit
-    omit_fields: Option<Vec<String>>,
+    omit_fields: Option<Vec<FieldPath>>,
     /// Expect only 1 result
     only: bool,
     /// Table name
-    table: String,
+    table: Ident,
+    /// What to select from instead of `table`
+    from: Option<Target>,
     relation: Option<(Thing, Thing)>,
 
     /// Filters (WHERE)
     filter: Filter,
 
-    /// Content, Merge or Patch
+    /// Content, Merge or Replace
     content: Option<BTreeMap<String, Value>>,
-
-    /// Orders (ORDER BY)
-    order: Option<Vec<String>>,
+    /// How `content` is applied, the statement's default if `None`
+    content_type: Option<ContentType>,
+    /// PATCH operations
+    patch: Option<Vec<PatchOperation>>,
+    /// SET assignments
+    set: Option<Vec<Assignment>>,
+    /// UNSET fields
+    unset: Option<Vec<FieldPath>>,
+
+    /// Records to insert
+    records: Option<Vec<BTreeMap<String, Value>>>,
+    /// INSERT IGNORE
+    ignore: bool,
+    /// INSERT RELATION
+    insert_relation: bool,
+    /// ON DUPLICATE KEY UPDATE
+    on_duplicate: Option<Vec<Assignment>>,
+
+    /// What to return (RETURN)
+    return_type: Option<Return>,
+
+    /// Orders (ORDER BY) as (field, direction)
+    order: Option<Vec<OrderBy>>,
     /// Groups (GROUP BY)
-    group_by: Option<Vec<String>>,
+    group_by: Option<Vec<FieldPath>>,
     group_all: bool,
 
     /// Limit
@@ -61,28 +104,42 @@ where
     start: Option<u64>,
 
     /// Fields to fetch
-    fetch_fields: Option<Vec<String>>,
+    fetch_fields: Option<Vec<FieldPath>>,
 
     timeout: Option<(usize, String)>,
 
     /// Parallel query
     parallel: bool,
+    /// LIVE SELECT DIFF
+    diff: bool,
+    /// Retries when run, overriding the executor's default
+    retry: Option<RetryPolicy>,
 
     phantom_data: PhantomData<Type>,
+    state: PhantomData<State>,
 }
 
-impl<Type> QueryBuilder<Type>
+impl<Type> QueryBuilder<Type, Type::State>
 where
     Type: Statement,
 {
-    const STATEMENT: &'static str = Type::STATEMENT;
-
-    pub fn new(table: &str) -> Self {
+    pub fn new(table: impl Into<Ident>) -> Self {
         Self {
             fields: None,
-            table: table.to_string(),
+            expressions: None,
+            table: table.into(),
+            from: None,
             filter: Filter::default(),
             content: None,
+            content_type: None,
+            patch: None,
+            set: None,
+            unset: None,
+            records: None,
+            ignore: false,
+            insert_relation: false,
+            on_duplicate: None,
+            return_type: None,
             order: None,
             group_by: None,
             group_all: false,
@@ -93,14 +150,110 @@ where
             omit_fields: None,
             timeout: None,
             parallel: false,
+            diff: false,
+            retry: None,
             relation: None,
 
             phantom_data: PhantomData,
+            state: PhantomData,
+        }
+    }
+}
+
+impl<Type, State> QueryBuilder<Type, State>
+where
+    Type: Statement + ?Sized,
+    State: BuilderState,
+{
+    #[inline]
+    pub(crate) fn set_retry_internal(mut self, retry: RetryPolicy) -> Self {
+        self.retry = Some(retry);
+
+        self
+    }
+
+    #[inline]
+    pub(crate) fn take_retry_internal(&mut self) -> Option<RetryPolicy> {
+        self.retry.take()
+    }
+
+    /// The table the statement reads or writes, for observers and metrics. Derived from the
+    /// target of a `SELECT` if it has one, `None` if it is a subquery.
+    #[inline]
+    pub(crate) fn table_internal(&self) -> Option<&str> {
+        match &self.from {
+            Some(from) => from.table(),
+            None => Some(self.table.as_str()),
         }
     }
+}
+
+impl<Type, State> QueryBuilder<Type, State>
+where
+    Type: Statement,
+    State: BuilderState,
+{
+    const STATEMENT: &'static str = Type::STATEMENT;
+
+    /// Moves the builder into another [`BuilderState`], keeping everything set so far.
+    #[inline]
+    fn into_state<Next>(self) -> QueryBuilder<Type, Next>
+    where
+        Next: BuilderState,
+    {
+        QueryBuilder {
+            fields: self.fields,
+            expressions: self.expressions,
+            omit_fields: self.omit_fields,
+            only: self.only,
+            table: self.table,
+            from: self.from,
+            relation: self.relation,
+            filter: self.filter,
+            content: self.content,
+            content_type: self.content_type,
+            patch: self.patch,
+            set: self.set,
+            unset: self.unset,
+            records: self.records,
+            ignore: self.ignore,
+            insert_relation: self.insert_relation,
+            on_duplicate: self.on_duplicate,
+            return_type: self.return_type,
+            order: self.order,
+            group_by: self.group_by,
+            group_all: self.group_all,
+            limit: self.limit,
+            start: self.start,
+            fetch_fields: self.fetch_fields,
+            timeout: self.timeout,
+            parallel: self.parallel,
+            diff: self.diff,
+            retry: self.retry,
+
+            phantom_data: PhantomData,
+            state: PhantomData,
+        }
+    }
+
+    #[inline]
+    fn add_expression_internal(mut self, expression: Expression, alias: Option<Ident>) -> Self {
+        self.expressions
+            .get_or_insert_with(Vec::new)
+            .push((expression, alias));
+
+        self
+    }
 
     #[inline]
-    fn add_field_internal(mut self, field: String, alias: Option<String>) -> Self {
+    fn set_from_internal(mut self, from: Target) -> Self {
+        self.from = Some(from);
+
+        self
+    }
+
+    #[inline]
+    fn add_field_internal(mut self, field: FieldPath, alias: Option<Ident>) -> Self {
         self.fields
             .get_or_insert_with(BTreeMap::new)
             .insert(field, alias);
@@ -109,7 +262,7 @@ where
     }
 
     #[inline]
-    fn add_fields_internal(mut self, fields: BTreeMap<String, Option<String>>) -> Self {
+    fn add_fields_internal(mut self, fields: BTreeMap<FieldPath, Option<Ident>>) -> Self {
         self.fields.get_or_insert_with(BTreeMap::new).extend(fields);
 
         self
@@ -123,14 +276,14 @@ where
     }
 
     #[inline]
-    fn set_fetch_fields_internal(mut self, fetch_fields: Vec<String>) -> Self {
+    fn set_fetch_fields_internal(mut self, fetch_fields: Vec<FieldPath>) -> Self {
         self.fetch_fields = Some(fetch_fields);
 
         self
     }
 
     #[inline]
-    fn set_fetch_field_internal(mut self, fetch_fields: String) -> Self {
+    fn set_fetch_field_internal(mut self, fetch_fields: FieldPath) -> Self {
         self.fetch_fields
             .get_or_insert_with(Vec::new)
             .push(fetch_fields);
@@ -139,14 +292,14 @@ where
     }
 
     #[inline]
-    fn set_omit_fields_internal(mut self, omit_fields: Vec<String>) -> Self {
+    fn set_omit_fields_internal(mut self, omit_fields: Vec<FieldPath>) -> Self {
         self.omit_fields = Some(omit_fields);
 
         self
     }
 
     #[inline]
-    fn set_omit_field_internal(mut self, omit_fields: String) -> Self {
+    fn set_omit_field_internal(mut self, omit_fields: FieldPath) -> Self {
         self.omit_fields
             .get_or_insert_with(Vec::new)
             .push(omit_fields);
@@ -161,6 +314,13 @@ where
         self
     }
 
+    #[inline]
+    fn set_diff_internal(mut self, diff: bool) -> Self {
+        self.diff = diff;
+
+        self
+    }
+
     #[inline]
     fn set_limit_internal(mut self, limit: u64) -> Self {
         self.limit = Some(limit);
@@ -185,7 +345,7 @@ where
     #[inline]
     fn add_condition_internal<T>(
         mut self,
-        field: String,
+        field: FieldPath,
         operator: Option<String>,
         value: T,
     ) -> Self
@@ -198,14 +358,62 @@ where
     }
 
     #[inline]
-    fn order_by_internal(mut self, order: Vec<String>) -> Self {
+    fn replace_condition_internal<T>(
+        mut self,
+        field: FieldPath,
+        operator: Option<String>,
+        value: T,
+    ) -> Self
+    where
+        Value: From<T>,
+    {
+        self.filter = self.filter.replace_condition(field, operator, value);
+
+        self
+    }
+
+    #[inline]
+    fn remove_condition_internal(mut self, field: FieldPath, operator: Option<String>) -> Self {
+        self.filter = self.filter.remove_condition(field, operator);
+
+        self
+    }
+
+    #[inline]
+    fn and_where_internal(mut self, condition: Condition) -> Self {
+        self.filter = self.filter.and(condition);
+
+        self
+    }
+
+    #[inline]
+    fn or_where_internal(mut self, condition: Condition) -> Self {
+        self.filter = self.filter.or(condition);
+
+        self
+    }
+
+    #[inline]
+    fn order_by_internal(mut self, order: Vec<OrderBy>) -> Self {
         self.order = Some(order);
 
         self
     }
 
     #[inline]
-    fn group_by_internal(mut self, group_by: Vec<String>) -> Self {
+    fn add_order_internal(mut self, order: OrderBy) -> Self {
+        self.order.get_or_insert_with(Vec::new).push(order);
+
+        self
+    }
+
+    #[inline]
+    fn order_internal(&self) -> &[OrderBy] {
+        self.order.as_deref().unwrap_or_default()
+    }
+
+    #[inline]
+    fn group_by_internal(mut self, group_by: Vec<FieldPath>) -> Self {
         self.group_by = Some(group_by);
 
         self
@@ -241,27 +449,201 @@ where
         self
     }
 
+    #[inline]
+    fn set_content_type_internal(mut self, content_type: ContentType) -> Self {
+        self.content_type = Some(content_type);
+
+        self
+    }
+
+    #[inline]
+    fn patch_internal(mut self, operation: PatchOperation) -> Self {
+        self.patch.get_or_insert_with(Vec::new).push(operation);
+
+        self
+    }
+
+    #[inline]
+    fn set_internal(mut self, assignment: Assignment) -> Self {
+        self.set.get_or_insert_with(Vec::new).push(assignment);
+
+        self
+    }
+
+    #[inline]
+    fn unset_internal(mut self, field: FieldPath) -> Self {
+        self.unset.get_or_insert_with(Vec::new).push(field);
+
+        self
+    }
+
+    /// Renders the data clause of an `UPDATE` or `UPSERT`: content, `PATCH`, or `SET`/`UNSET`.
+    /// Only one of them can be used, since a statement takes a single data clause.
+    fn data_clause(
+        &mut self,
+        default: ContentType,
+        query: &mut String,
+        bindings: &mut Bindings,
+    ) -> Result<(), QueryBuildError> {
+        let content = self.content.take();
+        let patch = self.patch.take();
+        let set = self.set.take().unwrap_or_default();
+        let unset = self.unset.take().unwrap_or_default();
+
+        let clauses = [
+            content.is_some(),
+            patch.is_some(),
+            !set.is_empty() || !unset.is_empty(),
+        ];
+
+        match clauses.iter().filter(|clause| **clause).count() {
+            0 => {
+                return Err(QueryBuildError::MissingContent {
+                    statement: Self::STATEMENT,
+                })
+            }
+            1 => {}
+            _ => {
+                return Err(QueryBuildError::ConflictingData {
+                    statement: Self::STATEMENT,
+                })
+            }
+        }
+
+        if content.is_some() {
+            let content_type = self.content_type.unwrap_or(default);
+            return query_parts::content(content_type, content, query, bindings);
+        }
+
+        if let Some(patch) = patch {
+            let patch = patch.into_iter().map(Value::from).collect::<Vec<_>>();
+            query.push_str(format!(" PATCH {}", bindings.bind(patch)).as_str());
+            return Ok(());
+        }
+
+        if set.is_empty() {
+            query.push_str(format!(" UNSET {}", query_parts::field_list(&unset)?).as_str());
+            return Ok(());
+        }
+
+        let mut assignments = set
+            .into_iter()
+            .map(|assignment| assignment.construct_with(bindings))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // `SET` and `UNSET` cannot be combined, so unset fields are set to `NONE` instead
+        for field in unset {
+            field.validate()?;
+            assignments.push(format!("{} = NONE", field));
+        }
+
+        query.push_str(format!(" SET {}", assignments.join(", ")).as_str());
+
+        Ok(())
+    }
+
     #[inline]
     fn relate_items_internal(mut self, from: Thing, to: Thing) -> Self {
         self.relation = Some((from, to));
 
         self
     }
+
+    #[inline]
+    fn add_record_internal(mut self, record: BTreeMap<String, Value>) -> Self {
+        self.records.get_or_insert_with(Vec::new).push(record);
+
+        self
+    }
+
+    #[inline]
+    fn add_records_internal(mut self, records: Vec<BTreeMap<String, Value>>) -> Self {
+        self.records.get_or_insert_with(Vec::new).extend(records);
+
+        self
+    }
+
+    #[inline]
+    fn set_ignore_internal(mut self, ignore: bool) -> Self {
+        self.ignore = ignore;
+
+        self
+    }
+
+    #[inline]
+    fn set_insert_relation_internal(mut self, insert_relation: bool) -> Self {
+        self.insert_relation = insert_relation;
+
+        self
+    }
+
+    #[inline]
+    fn on_duplicate_internal(mut self, assignment: Assignment) -> Self {
+        self.on_duplicate
+            .get_or_insert_with(Vec::new)
+            .push(assignment);
+
+        self
+    }
+
+    #[inline]
+    fn set_return_internal(mut self, return_type: Return) -> Self {
+        self.return_type = Some(return_type);
+
+        self
+    }
 }
 
 #[macro_export]
 macro_rules! impl_filter {
     () => {
-        pub fn set_filter(self, filter: crate::db::Filter) -> Self {
+        pub fn set_filter(self, filter: $crate::db::Filter) -> Self {
             self.set_filter_internal(filter)
         }
 
-        pub fn add_condition<T>(self, field: &str, operator: Option<&str>, value: T) -> Self
+        pub fn add_condition<T>(
+            self,
+            field: impl Into<$crate::db::FieldPath>,
+            operator: Option<&str>,
+            value: T,
+        ) -> Self
+        where
+            surrealdb::sql::Value: From<T>,
+        {
+            let operator = operator.map(|o| o.to_string());
+            self.add_condition_internal(field.into(), operator, value)
+        }
+
+        pub fn replace_condition<T>(
+            self,
+            field: impl Into<$crate::db::FieldPath>,
+            operator: Option<&str>,
+            value: T,
+        ) -> Self
         where
             surrealdb::sql::Value: From<T>,
         {
             let operator = operator.map(|o| o.to_string());
-            self.add_condition_internal(field.to_string(), operator, value)
+            self.replace_condition_internal(field.into(), operator, value)
+        }
+
+        pub fn remove_condition(
+            self,
+            field: impl Into<$crate::db::FieldPath>,
+            operator: Option<&str>,
+        ) -> Self {
+            let operator = operator.map(|o| o.to_string());
+            self.remove_condition_internal(field.into(), operator)
+        }
+
+        /// Adds `condition` to the filter, joined with `AND`.
+        pub fn and_where(self, condition: $crate::db::Condition) -> Self {
+            self.and_where_internal(condition)
+        }
+
+        /// Joins the filter so far with `condition` using `OR`.
+        pub fn or_where(self, condition: $crate::db::Condition) -> Self {
+            self.or_where_internal(condition)
         }
     };
 }
@@ -287,8 +669,23 @@ macro_rules! impl_start {
 #[macro_export]
 macro_rules! impl_order_by {
     () => {
-        pub fn order_by(self, order: Vec<&str>) -> Self {
-            self.order_by_internal(order.iter().map(|s| s.to_string()).collect())
+        /// Replaces the sort keys. Strings are parsed as `"field [ASC|DESC]"`, see
+        /// [`OrderBy`]($crate::db::OrderBy).
+        pub fn order_by<O>(self, order: impl IntoIterator<Item = O>) -> Self
+        where
+            O: Into<$crate::db::OrderBy>,
+        {
+            self.order_by_internal(order.into_iter().map(Into::into).collect())
+        }
+
+        /// Appends a sort key after the existing ones.
+        pub fn add_order(self, order: impl Into<$crate::db::OrderBy>) -> Self {
+            self.add_order_internal(order.into())
+        }
+
+        /// The sort keys in the order they are applied.
+        pub fn order(&self) -> &[$crate::db::OrderBy] {
+            self.order_internal()
         }
     };
 }
@@ -296,8 +693,11 @@ macro_rules! impl_order_by {
 #[macro_export]
 macro_rules! impl_group_by {
     () => {
-        pub fn group_by(self, group_by: Vec<&str>) -> Self {
-            self.group_by_internal(group_by.iter().map(|s| s.to_string()).collect())
+        pub fn group_by<F>(self, group_by: Vec<F>) -> Self
+        where
+            F: Into<$crate::db::FieldPath>,
+        {
+            self.group_by_internal(group_by.into_iter().map(Into::into).collect())
         }
     };
 }
@@ -305,15 +705,45 @@ macro_rules! impl_group_by {
 #[macro_export]
 macro_rules! impl_fields {
     () => {
-        pub fn add_field(self, field: &str, alias: Option<&str>) -> Self {
-            self.add_field_internal(field.to_string(), alias.map(|s| s.to_string()))
+        pub fn add_field(
+            self,
+            field: impl Into<$crate::db::FieldPath>,
+            alias: Option<&str>,
+        ) -> Self {
+            self.add_field_internal(field.into(), alias.map(Into::into))
+        }
+
+        /// Selects every field with its optional alias, e.g. from a map or a list of pairs.
+        pub fn add_fields<F, A>(self, fields: impl IntoIterator<Item = (F, Option<A>)>) -> Self
+        where
+            F: Into<$crate::db::FieldPath>,
+            A: Into<$crate::db::Ident>,
+        {
+            self.add_fields_internal(
+                fields
+                    .into_iter()
+                    .map(|(field, alias)| (field.into(), alias.map(Into::into)))
+                    .collect(),
+            )
         }
 
-        pub fn add_fields(
+        /// Selects a graph traversal or subquery, after the plain fields.
+        pub fn add_expression(
             self,
-            fields: std::collections::BTreeMap<String, Option<String>>,
+            expression: impl Into<$crate::db::Expression>,
+            alias: Option<&str>,
         ) -> Self {
-            self.add_fields_internal(fields)
+            self.add_expression_internal(expression.into(), alias.map(Into::into))
+        }
+    };
+}
+
+#[macro_export]
+macro_rules! impl_target {
+    () => {
+        /// Runs on a record, record range, graph traversal or subquery instead of the table.
+        pub fn set_from(self, from: impl Into<$crate::db::Target>) -> Self {
+            self.set_from_internal(from.into())
         }
     };
 }
@@ -330,12 +760,15 @@ macro_rules! impl_only {
 #[macro_export]
 macro_rules! impl_fetch_fields {
     () => {
-        pub fn set_fetch_fields(self, fetch_fields: Vec<&str>) -> Self {
-            self.set_fetch_fields_internal(fetch_fields.iter().map(|s| s.to_string()).collect())
+        pub fn set_fetch_fields<F>(self, fetch_fields: Vec<F>) -> Self
+        where
+            F: Into<$crate::db::FieldPath>,
+        {
+            self.set_fetch_fields_internal(fetch_fields.into_iter().map(Into::into).collect())
         }
 
-        pub fn set_fetch_field(self, fetch_field: &str) -> Self {
-            self.set_fetch_field_internal(fetch_field.to_string())
+        pub fn set_fetch_field(self, fetch_field: impl Into<$crate::db::FieldPath>) -> Self {
+            self.set_fetch_field_internal(fetch_field.into())
         }
     };
 }
@@ -343,12 +776,15 @@ macro_rules! impl_fetch_fields {
 #[macro_export]
 macro_rules! impl_omit_fields {
     () => {
-        pub fn set_omit_fields(self, omit_fields: Vec<&str>) -> Self {
-            self.set_omit_fields_internal(omit_fields.iter().map(|s| s.to_string()).collect())
+        pub fn set_omit_fields<F>(self, omit_fields: Vec<F>) -> Self
+        where
+            F: Into<$crate::db::FieldPath>,
+        {
+            self.set_omit_fields_internal(omit_fields.into_iter().map(Into::into).collect())
         }
 
-        pub fn set_omit_field(self, omit_field: &str) -> Self {
-            self.set_omit_field_internal(omit_field.to_string())
+        pub fn set_omit_field(self, omit_field: impl Into<$crate::db::FieldPath>) -> Self {
+            self.set_omit_field_internal(omit_field.into())
         }
     };
 }
@@ -362,6 +798,16 @@ macro_rules! impl_parallel {
     };
 }
 
+#[macro_export]
+macro_rules! impl_diff {
+    () => {
+        /// Receives the changes of a record as JSON Patch operations instead of the record.
+        pub fn set_diff(self, diff: bool) -> Self {
+            self.set_diff_internal(diff)
+        }
+    };
+}
+
 #[macro_export]
 macro_rules! impl_group_all {
     () => {
@@ -398,14 +844,263 @@ macro_rules! impl_content {
         ) -> Self {
             self.set_content_internal(content)
         }
+
+        /// Serializes `content` into an object and uses it as the content, keeping every field.
+        pub fn set_content_from<T>(self, content: &T) -> $crate::Result<Self>
+        where
+            T: serde::Serialize + Clone + 'static,
+        {
+            self.set_content_from_with(content, $crate::db::ContentOptions::default())
+        }
+
+        /// Serializes `content` into an object and uses it as the content, leaving out the
+        /// fields `options` skips.
+        pub fn set_content_from_with<T>(
+            self,
+            content: &T,
+            options: $crate::db::ContentOptions,
+        ) -> $crate::Result<Self>
+        where
+            T: serde::Serialize + Clone + 'static,
+        {
+            let content = $crate::db::query::query_parts::to_content(content.clone(), options)?;
+            Ok(self.set_content_internal(content))
+        }
+    };
+
+    // Setting content completes the builder
+    ($statement:ty) => {
+        pub fn add_field_to_content<T>(
+            self,
+            field: &str,
+            content: T,
+        ) -> $crate::db::QueryBuilder<$statement, $crate::db::Ready>
+        where
+            surrealdb::sql::Value: From<T>,
+            T: Into<surrealdb::sql::Value>,
+        {
+            self.add_field_to_content_internal(field.to_string(), content.into())
+                .into_state()
+        }
+
+        pub fn set_content(
+            self,
+            content: std::collections::BTreeMap<String, surrealdb::sql::Value>,
+        ) -> $crate::db::QueryBuilder<$statement, $crate::db::Ready> {
+            self.set_content_internal(content).into_state()
+        }
+
+        /// Serializes `content` into an object and uses it as the content, keeping every field.
+        pub fn set_content_from<T>(
+            self,
+            content: &T,
+        ) -> $crate::Result<$crate::db::QueryBuilder<$statement, $crate::db::Ready>>
+        where
+            T: serde::Serialize + Clone + 'static,
+        {
+            self.set_content_from_with(content, $crate::db::ContentOptions::default())
+        }
+
+        /// Serializes `content` into an object and uses it as the content, leaving out the
+        /// fields `options` skips.
+        pub fn set_content_from_with<T>(
+            self,
+            content: &T,
+            options: $crate::db::ContentOptions,
+        ) -> $crate::Result<$crate::db::QueryBuilder<$statement, $crate::db::Ready>>
+        where
+            T: serde::Serialize + Clone + 'static,
+        {
+            let content = $crate::db::query::query_parts::to_content(content.clone(), options)?;
+            Ok(self.set_content_internal(content).into_state())
+        }
     };
 }
 
 #[macro_export]
 macro_rules! impl_relation {
+    ($statement:ty) => {
+        pub fn relate_items(
+            self,
+            from: surrealdb::sql::Thing,
+            to: surrealdb::sql::Thing,
+        ) -> $crate::db::QueryBuilder<$statement, $crate::db::Ready> {
+            self.relate_items_internal(from, to).into_state()
+        }
+    };
+}
+
+#[macro_export]
+macro_rules! impl_records {
     () => {
-        pub fn relate_items(self, from: surrealdb::sql::Thing, to: surrealdb::sql::Thing) -> Self {
-            self.relate_items_internal(from, to)
+        pub fn add_record(
+            self,
+            record: std::collections::BTreeMap<String, surrealdb::sql::Value>,
+        ) -> Self {
+            self.add_record_internal(record)
+        }
+
+        pub fn add_records(
+            self,
+            records: Vec<std::collections::BTreeMap<String, surrealdb::sql::Value>>,
+        ) -> Self {
+            self.add_records_internal(records)
+        }
+
+        /// Serializes `record` into an object and adds it to the records to insert.
+        pub fn add_record_from<T>(self, record: &T) -> $crate::Result<Self>
+        where
+            T: serde::Serialize + Clone + 'static,
+        {
+            let record = $crate::db::query::query_parts::to_object(record.clone())?;
+            Ok(self.add_record_internal(record))
+        }
+
+        /// Serializes every item in `records` and adds them to the records to insert.
+        pub fn add_records_from<T>(self, records: &[T]) -> $crate::Result<Self>
+        where
+            T: serde::Serialize + Clone + 'static,
+        {
+            let records = records
+                .iter()
+                .map(|record| $crate::db::query::query_parts::to_object(record.clone()))
+                .collect::<$crate::InternalResult<Vec<_>>>()?;
+            Ok(self.add_records_internal(records))
+        }
+    };
+}
+
+#[macro_export]
+macro_rules! impl_ignore {
+    () => {
+        pub fn set_ignore(self, ignore: bool) -> Self {
+            self.set_ignore_internal(ignore)
+        }
+    };
+}
+
+#[macro_export]
+macro_rules! impl_insert_relation {
+    () => {
+        pub fn set_relation(self, relation: bool) -> Self {
+            self.set_insert_relation_internal(relation)
+        }
+    };
+}
+
+#[macro_export]
+macro_rules! impl_on_duplicate {
+    () => {
+        pub fn on_duplicate(self, assignment: $crate::db::Assignment) -> Self {
+            self.on_duplicate_internal(assignment)
+        }
+
+        pub fn on_duplicate_set<T>(self, field: impl Into<$crate::db::FieldPath>, value: T) -> Self
+        where
+            surrealdb::sql::Value: From<T>,
+        {
+            self.on_duplicate_internal($crate::db::Assignment::set(field, value))
+        }
+    };
+}
+
+#[macro_export]
+macro_rules! impl_return {
+    () => {
+        pub fn set_return(self, return_type: $crate::db::Return) -> Self {
+            self.set_return_internal(return_type)
+        }
+    };
+}
+
+/// Content modes, `PATCH` and `SET`/`UNSET` for statements that modify existing records. Each of
+/// them completes the builder.
+#[macro_export]
+macro_rules! impl_data {
+    ($statement:ty) => {
+        /// Sets how the content is applied, instead of the statement's default.
+        pub fn set_content_type(self, content_type: $crate::db::ContentType) -> Self {
+            self.set_content_type_internal(content_type)
+        }
+
+        /// `MERGE content`
+        pub fn merge(
+            self,
+            content: std::collections::BTreeMap<String, surrealdb::sql::Value>,
+        ) -> $crate::db::QueryBuilder<$statement, $crate::db::Ready> {
+            self.set_content_internal(content)
+                .set_content_type_internal($crate::db::ContentType::Merge)
+                .into_state()
+        }
+
+        /// `REPLACE content`
+        pub fn replace(
+            self,
+            content: std::collections::BTreeMap<String, surrealdb::sql::Value>,
+        ) -> $crate::db::QueryBuilder<$statement, $crate::db::Ready> {
+            self.set_content_internal(content)
+                .set_content_type_internal($crate::db::ContentType::Replace)
+                .into_state()
+        }
+
+        /// Adds a JSON Patch operation, all of them are applied in order with `PATCH`.
+        pub fn patch(
+            self,
+            operation: $crate::db::PatchOperation,
+        ) -> $crate::db::QueryBuilder<$statement, $crate::db::Ready> {
+            self.patch_internal(operation).into_state()
+        }
+
+        /// Adds an assignment to the `SET` clause.
+        pub fn assign(
+            self,
+            assignment: $crate::db::Assignment,
+        ) -> $crate::db::QueryBuilder<$statement, $crate::db::Ready> {
+            self.set_internal(assignment).into_state()
+        }
+
+        /// `SET field = value`
+        pub fn set<T>(
+            self,
+            field: impl Into<$crate::db::FieldPath>,
+            value: T,
+        ) -> $crate::db::QueryBuilder<$statement, $crate::db::Ready>
+        where
+            surrealdb::sql::Value: From<T>,
+        {
+            self.assign($crate::db::Assignment::set(field, value))
+        }
+
+        /// `SET field += value`
+        pub fn increment<T>(
+            self,
+            field: impl Into<$crate::db::FieldPath>,
+            value: T,
+        ) -> $crate::db::QueryBuilder<$statement, $crate::db::Ready>
+        where
+            surrealdb::sql::Value: From<T>,
+        {
+            self.assign($crate::db::Assignment::increment(field, value))
+        }
+
+        /// `SET field -= value`
+        pub fn decrement<T>(
+            self,
+            field: impl Into<$crate::db::FieldPath>,
+            value: T,
+        ) -> $crate::db::QueryBuilder<$statement, $crate::db::Ready>
+        where
+            surrealdb::sql::Value: From<T>,
+        {
+            self.assign($crate::db::Assignment::decrement(field, value))
+        }
+
+        /// `UNSET field`
+        pub fn unset(
+            self,
+            field: impl Into<$crate::db::FieldPath>,
+        ) -> $crate::db::QueryBuilder<$statement, $crate::db::Ready> {
+            self.unset_internal(field.into()).into_state()
         }
     };
 }
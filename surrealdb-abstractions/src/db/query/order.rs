@@ -0,0 +1,181 @@
+use crate::db::{FieldPath, QueryBuildError};
+use std::fmt::{Display, Formatter};
+
+/// The direction of an [`OrderBy`] key.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Direction {
+    #[default]
+    Asc,
+    Desc,
+}
+
+impl Direction {
+    /// The opposite direction.
+    pub fn reverse(self) -> Self {
+        match self {
+            Direction::Asc => Direction::Desc,
+            Direction::Desc => Direction::Asc,
+        }
+    }
+}
+
+impl Display for Direction {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Direction::Asc => f.write_str("ASC"),
+            Direction::Desc => f.write_str("DESC"),
+        }
+    }
+}
+
+/// A single `ORDER BY` key.
+///
+/// # Examples
+///
+/// ```
+/// # use surrealdb_abstraction::db::OrderBy;
+///
+/// assert_eq!(OrderBy::desc("age").to_string(), "age DESC");
+/// assert_eq!(OrderBy::asc("name").collate().numeric().to_string(), "name COLLATE NUMERIC ASC");
+/// assert_eq!(OrderBy::rand().to_string(), "RAND()");
+///
+/// // Strings are parsed as `field [ASC|DESC]`
+/// assert_eq!(OrderBy::from("created_at desc"), OrderBy::desc("created_at"));
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum OrderBy {
+    /// `field [COLLATE] [NUMERIC] ASC|DESC`
+    Field {
+        field: FieldPath,
+        direction: Direction,
+        collate: bool,
+        numeric: bool,
+    },
+    /// `RAND()`
+    Rand,
+}
+
+impl OrderBy {
+    pub fn new(field: impl Into<FieldPath>, direction: Direction) -> Self {
+        OrderBy::Field {
+            field: field.into(),
+            direction,
+            collate: false,
+            numeric: false,
+        }
+    }
+
+    pub fn asc(field: impl Into<FieldPath>) -> Self {
+        Self::new(field, Direction::Asc)
+    }
+
+    pub fn desc(field: impl Into<FieldPath>) -> Self {
+        Self::new(field, Direction::Desc)
+    }
+
+    /// Orders the results randomly.
+    pub fn rand() -> Self {
+        OrderBy::Rand
+    }
+
+    /// Sorts strings with unicode collation. Has no effect on `RAND()`.
+    pub fn collate(mut self) -> Self {
+        if let OrderBy::Field { collate, .. } = &mut self {
+            *collate = true;
+        }
+
+        self
+    }
+
+    /// Sorts numbers inside strings numerically. Has no effect on `RAND()`.
+    pub fn numeric(mut self) -> Self {
+        if let OrderBy::Field { numeric, .. } = &mut self {
+            *numeric = true;
+        }
+
+        self
+    }
+
+    /// The field this key sorts by, `None` for `RAND()`.
+    pub fn field(&self) -> Option<&FieldPath> {
+        match self {
+            OrderBy::Field { field, .. } => Some(field),
+            OrderBy::Rand => None,
+        }
+    }
+
+    /// The direction this key sorts in, `None` for `RAND()`.
+    pub fn direction(&self) -> Option<Direction> {
+        match self {
+            OrderBy::Field { direction, .. } => Some(*direction),
+            OrderBy::Rand => None,
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), QueryBuildError> {
+        match self {
+            OrderBy::Field { field, .. } => field.validate(),
+            OrderBy::Rand => Ok(()),
+        }
+    }
+}
+
+impl Display for OrderBy {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OrderBy::Field {
+                field,
+                direction,
+                collate,
+                numeric,
+            } => {
+                write!(f, "{}", field)?;
+
+                if *collate {
+                    f.write_str(" COLLATE")?;
+                }
+
+                if *numeric {
+                    f.write_str(" NUMERIC")?;
+                }
+
+                write!(f, " {}", direction)
+            }
+            OrderBy::Rand => f.write_str("RAND()"),
+        }
+    }
+}
+
+impl From<&str> for OrderBy {
+    fn from(order: &str) -> Self {
+        let order = order.trim();
+
+        if order.eq_ignore_ascii_case("rand()") {
+            return OrderBy::Rand;
+        }
+
+        if let Some((field, direction)) = order.rsplit_once(' ') {
+            if direction.eq_ignore_ascii_case("asc") {
+                return Self::asc(field.trim_end());
+            }
+
+            if direction.eq_ignore_ascii_case("desc") {
+                return Self::desc(field.trim_end());
+            }
+        }
+
+        Self::asc(order)
+    }
+}
+
+impl From<String> for OrderBy {
+    fn from(order: String) -> Self {
+        Self::from(order.as_str())
+    }
+}
+
+impl From<FieldPath> for OrderBy {
+    fn from(field: FieldPath) -> Self {
+        Self::asc(field)
+    }
+}
@@ -0,0 +1,242 @@
+use crate::db::error::DatabaseError;
+use crate::db::runner::Runner;
+use crate::db::{
+    Condition, Direction, Executor, FieldPath, OrderBy, QueryBuildError, QueryBuilder, Select,
+};
+use crate::{InternalResult, Result};
+use futures::stream::{self, Stream, TryStreamExt};
+use serde::de::DeserializeOwned;
+use surrealdb::sql::Value;
+
+/// Walks the results of a `SELECT` page by page.
+///
+/// Pages are fetched with keyset pagination: every page continues right after the last row of
+/// the previous one using the query's sort keys, instead of skipping rows with `START`. The
+/// record `id` is added as a final sort key so rows with equal keys are never skipped or
+/// repeated. Sort keys must be plain fields without `COLLATE` or `NUMERIC`, and every row must
+/// contain them, so keep them in the selected fields.
+///
+/// Any `LIMIT` or `START` set on the query is replaced by the page size.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use futures::TryStreamExt;
+/// # use surrealdb::engine::local::Db;
+/// # use surrealdb::Surreal;
+/// # use surrealdb_abstraction::db::{OrderBy, Select, Statement};
+/// # #[derive(serde::Deserialize)]
+/// # struct User { name: String }
+/// # async fn example(db: &Surreal<Db>) -> surrealdb_abstraction::Result<()> {
+/// let users = Select::query("user")
+///     .order_by([OrderBy::asc("name")])
+///     .paginate(500)
+///     .stream::<_, User>(db);
+/// let mut users = std::pin::pin!(users);
+///
+/// while let Some(user) = users.try_next().await? {
+///     println!("{}", user.name);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct Paginator {
+    query: QueryBuilder<Select>,
+    page_size: u64,
+    cursor: Option<Vec<Value>>,
+}
+
+impl QueryBuilder<Select> {
+    /// Paginates over the results `page_size` rows at a time, see [`Paginator`].
+    pub fn paginate(self, page_size: u64) -> Paginator {
+        Paginator {
+            query: self,
+            page_size,
+            cursor: None,
+        }
+    }
+}
+
+impl Paginator {
+    /// The keys pages are walked by: the query's sort keys followed by `id`, unless the query
+    /// already orders by it.
+    pub fn sort_keys(&self) -> std::result::Result<Vec<OrderBy>, QueryBuildError> {
+        Ok(self
+            .keys()?
+            .into_iter()
+            .map(|(field, direction)| OrderBy::new(field, direction))
+            .collect())
+    }
+
+    /// Streams every page in order. The stream ends after the first short or empty page.
+    ///
+    /// `db` is cloned for every page, so pass a reference or a cheaply cloned handle like an
+    /// `Arc`.
+    pub fn pages<E, T>(self, db: E) -> impl Stream<Item = Result<Vec<T>>>
+    where
+        E: Executor + Clone,
+        T: DeserializeOwned,
+    {
+        stream::try_unfold(Some(self), move |paginator| {
+            let db = db.clone();
+
+            async move {
+                match paginator {
+                    Some(paginator) => paginator.next_page(&db).await.map_err(Into::into),
+                    None => Ok(None),
+                }
+            }
+        })
+    }
+
+    /// Streams every row, fetching the next page only once the current one is consumed.
+    pub fn stream<E, T>(self, db: E) -> impl Stream<Item = Result<T>>
+    where
+        E: Executor + Clone,
+        T: DeserializeOwned,
+    {
+        self.pages(db)
+            .map_ok(|page| stream::iter(page.into_iter().map(Ok)))
+            .try_flatten()
+    }
+
+    fn keys(&self) -> std::result::Result<Vec<(FieldPath, Direction)>, QueryBuildError> {
+        let id = FieldPath::from("id");
+        let mut keys = Vec::new();
+
+        for order in self.query.order() {
+            match order {
+                OrderBy::Field {
+                    field,
+                    direction,
+                    collate: false,
+                    numeric: false,
+                } if field.parts().is_some() => keys.push((field.clone(), *direction)),
+                order => {
+                    return Err(QueryBuildError::InvalidPaginationOrder(order.to_string()));
+                }
+            }
+        }
+
+        if !keys.iter().any(|(field, _)| *field == id) {
+            keys.push((id, Direction::Asc));
+        }
+
+        Ok(keys)
+    }
+
+    /// Fetches the page after the cursor, returning it along with the paginator for the next
+    /// page, which is `None` once the results are exhausted.
+    async fn next_page<E, T>(self, db: &E) -> InternalResult<Option<(Vec<T>, Option<Self>)>>
+    where
+        E: Executor,
+        T: DeserializeOwned,
+    {
+        if self.page_size == 0 {
+            return Err(QueryBuildError::ZeroPageSize.into());
+        }
+
+        let keys = self.keys()?;
+
+        let mut query = self.query.clone();
+        query.limit = Some(self.page_size);
+        query.start = None;
+        query.order = Some(
+            keys.iter()
+                .map(|(field, direction)| OrderBy::new(field.clone(), *direction))
+                .collect(),
+        );
+
+        if let Some(cursor) = &self.cursor {
+            query.filter = query.filter.and(after_cursor(&keys, cursor));
+        }
+
+        let table = query.table_internal().map(ToString::to_string);
+
+        let rows = match Runner::run(db, query, table.as_deref(), 0)
+            .await?
+            .into_inner()
+        {
+            Value::Array(rows) => rows.0,
+            Value::None | Value::Null => Vec::new(),
+            other => vec![other],
+        };
+
+        let Some(last) = rows.last() else {
+            return Ok(None);
+        };
+
+        let next = if (rows.len() as u64) < self.page_size {
+            None
+        } else {
+            let cursor = keys
+                .iter()
+                .map(|(field, _)| pick(last, field))
+                .collect::<InternalResult<Vec<_>>>()?;
+
+            Some(Self {
+                cursor: Some(cursor),
+                ..self
+            })
+        };
+
+        let page = rows
+            .into_iter()
+            .map(|row| {
+                surrealdb::sql::from_value(row)
+                    .map_err(|err| DatabaseError::DeserializationError(err.to_string()))
+            })
+            .collect::<InternalResult<Vec<T>>>()?;
+
+        Ok(Some((page, next)))
+    }
+}
+
+/// Matches the rows that sort after `cursor`:
+/// `k0 > v0 OR (k0 = v0 AND k1 > v1) OR ...`, with `<` for descending keys.
+fn after_cursor(keys: &[(FieldPath, Direction)], cursor: &[Value]) -> Condition {
+    let mut branches = (0..keys.len())
+        .map(|i| {
+            let (field, direction) = &keys[i];
+            let operator = match direction {
+                Direction::Asc => ">",
+                Direction::Desc => "<",
+            };
+
+            keys[..i]
+                .iter()
+                .zip(cursor)
+                .map(|((field, _), value)| Condition::eq(field.clone(), value.clone()))
+                .chain([Condition::compare(
+                    field.clone(),
+                    operator,
+                    cursor[i].clone(),
+                )])
+                .reduce(Condition::and)
+                .expect("every branch has a comparison")
+        })
+        .collect::<Vec<_>>();
+
+    if branches.len() == 1 {
+        branches.remove(0)
+    } else {
+        Condition::Or(branches)
+    }
+}
+
+/// Reads the value at `field` from a row.
+fn pick(row: &Value, field: &FieldPath) -> InternalResult<Value> {
+    let missing =
+        || DatabaseError::SerializationError(format!("row is missing sort key `{}`", field));
+
+    field
+        .parts()
+        .unwrap_or_default()
+        .iter()
+        .try_fold(row, |value, part| match value {
+            Value::Object(object) => object.get(part.as_str()).ok_or_else(missing),
+            _ => Err(missing()),
+        })
+        .cloned()
+}
@@ -0,0 +1,117 @@
+use surrealdb::sql::{Object, Value};
+
+/// A single RFC 6902 JSON Patch operation, applied with `PATCH`.
+///
+/// Paths are JSON Pointers, e.g. `/address/city` or `/tags/0`.
+///
+/// # Examples
+///
+/// ```
+/// # use surrealdb_abstraction::db::PatchOperation;
+/// # use surrealdb::sql::Value;
+///
+/// let op = Value::from(PatchOperation::replace("/name", "Alice"));
+///
+/// assert_eq!(op.to_string(), "{ op: 'replace', path: '/name', value: 'Alice' }");
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub enum PatchOperation {
+    Add { path: String, value: Value },
+    Remove { path: String },
+    Replace { path: String, value: Value },
+    Move { from: String, path: String },
+    Copy { from: String, path: String },
+    Test { path: String, value: Value },
+}
+
+impl PatchOperation {
+    pub fn add<T>(path: impl Into<String>, value: T) -> Self
+    where
+        Value: From<T>,
+    {
+        PatchOperation::Add {
+            path: path.into(),
+            value: Value::from(value),
+        }
+    }
+
+    pub fn remove(path: impl Into<String>) -> Self {
+        PatchOperation::Remove { path: path.into() }
+    }
+
+    pub fn replace<T>(path: impl Into<String>, value: T) -> Self
+    where
+        Value: From<T>,
+    {
+        PatchOperation::Replace {
+            path: path.into(),
+            value: Value::from(value),
+        }
+    }
+
+    pub fn move_to(from: impl Into<String>, path: impl Into<String>) -> Self {
+        PatchOperation::Move {
+            from: from.into(),
+            path: path.into(),
+        }
+    }
+
+    pub fn copy_to(from: impl Into<String>, path: impl Into<String>) -> Self {
+        PatchOperation::Copy {
+            from: from.into(),
+            path: path.into(),
+        }
+    }
+
+    /// Fails the whole patch unless the value at `path` equals `value`.
+    pub fn test<T>(path: impl Into<String>, value: T) -> Self
+    where
+        Value: From<T>,
+    {
+        PatchOperation::Test {
+            path: path.into(),
+            value: Value::from(value),
+        }
+    }
+
+    /// The `op` member of the operation.
+    pub fn op(&self) -> &'static str {
+        match self {
+            PatchOperation::Add { .. } => "add",
+            PatchOperation::Remove { .. } => "remove",
+            PatchOperation::Replace { .. } => "replace",
+            PatchOperation::Move { .. } => "move",
+            PatchOperation::Copy { .. } => "copy",
+            PatchOperation::Test { .. } => "test",
+        }
+    }
+}
+
+impl From<PatchOperation> for Value {
+    fn from(operation: PatchOperation) -> Self {
+        let mut object = Object::default();
+        object.insert("op".to_string(), Value::from(operation.op()));
+
+        let (from, path, value) = match operation {
+            PatchOperation::Add { path, value }
+            | PatchOperation::Replace { path, value }
+            | PatchOperation::Test { path, value } => (None, path, Some(value)),
+            PatchOperation::Remove { path } => (None, path, None),
+            PatchOperation::Move { from, path } | PatchOperation::Copy { from, path } => {
+                (Some(from), path, None)
+            }
+        };
+
+        if let Some(from) = from {
+            object.insert("from".to_string(), Value::from(from));
+        }
+
+        object.insert("path".to_string(), Value::from(path));
+
+        if let Some(value) = value {
+            object.insert("value".to_string(), value);
+        }
+
+        Value::Object(object)
+    }
+}
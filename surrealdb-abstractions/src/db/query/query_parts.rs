@@ -1,5 +1,8 @@
-use crate::db::query::ContentType;
+use crate::db::query::ident::escape_key;
+use crate::db::query::{Bindings, ContentOptions, ContentType, FieldPath, Ident, Return};
+use crate::db::{DatabaseError, QueryBuildError};
 use crate::prelude::Filter;
+use crate::InternalResult;
 use std::collections::BTreeMap;
 use surrealdb::sql::Value;
 
@@ -8,31 +11,60 @@ pub(crate) fn content(
     content_type: ContentType,
     content: Option<BTreeMap<String, Value>>,
     query: &mut String,
-) {
+    bindings: &mut Bindings,
+) -> Result<(), QueryBuildError> {
     if let Some(content) = content {
         match content_type {
-            ContentType::Content => query.push_str(" CONTENT {"),
-            ContentType::Merge => query.push_str(" Merge {"),
-            ContentType::Patch => query.push_str(" PATCH {"),
+            ContentType::Content => query.push_str(" CONTENT { "),
+            ContentType::Merge => query.push_str(" MERGE { "),
+            ContentType::Replace => query.push_str(" REPLACE { "),
         }
 
         let mut contents = Vec::new();
 
         for (field, value) in content {
-            contents.push(format!(" {}: {}", field, value));
+            contents.push(format!("{}: {}", escape_key(&field), bindings.bind(value)));
         }
 
         query.push_str(&contents.join(", "));
-        query.push('}');
+        query.push_str(" }");
     }
+
+    Ok(())
 }
 
 #[inline]
-pub(crate) fn filter(filter: Filter, query: &mut String) {
-    let filter_clause = filter.construct();
+pub(crate) fn filter(
+    filter: Filter,
+    query: &mut String,
+    bindings: &mut Bindings,
+) -> Result<(), QueryBuildError> {
+    let filter_clause = filter.construct_with(bindings)?;
     if !filter_clause.is_empty() {
+        query.push(' ');
         query.push_str(&filter_clause);
     }
+
+    Ok(())
+}
+
+#[inline]
+pub(crate) fn validate_table(table: &Ident) -> Result<(), QueryBuildError> {
+    if table.is_empty() {
+        return Err(QueryBuildError::EmptyTableName);
+    }
+
+    table.validate()
+}
+
+/// Validates every field and renders them as a comma separated list.
+pub(crate) fn field_list(fields: &[FieldPath]) -> Result<String, QueryBuildError> {
+    let fields = fields
+        .iter()
+        .map(|field| field.validate().map(|_| field.to_string()))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(fields.join(", "))
 }
 
 #[inline]
@@ -48,3 +80,53 @@ pub(crate) fn parallel(parallel: bool, query: &mut String) {
         query.push_str(" PARALLEL");
     }
 }
+
+#[inline]
+pub(crate) fn return_type(
+    return_type: Option<Return>,
+    query: &mut String,
+) -> Result<(), QueryBuildError> {
+    if let Some(return_type) = return_type {
+        match &return_type {
+            Return::Fields(fields) => fields.iter().try_for_each(FieldPath::validate)?,
+            Return::Value(expr) => expr.validate()?,
+            _ => {}
+        }
+
+        query.push_str(format!(" {}", return_type).as_str());
+    }
+
+    Ok(())
+}
+
+/// Serializes `value` into content, leaving out the fields `options` skips.
+pub(crate) fn to_content<T>(
+    value: T,
+    options: ContentOptions,
+) -> InternalResult<BTreeMap<String, Value>>
+where
+    T: serde::Serialize + 'static,
+{
+    let mut content = to_object(value)?;
+
+    content.retain(|field, value| {
+        !(options.skip_id && field == "id" || options.skip_none && value.is_none())
+    });
+
+    Ok(content)
+}
+
+/// Serializes `value` and returns its fields, failing if it does not serialize to an object.
+pub(crate) fn to_object<T>(value: T) -> InternalResult<BTreeMap<String, Value>>
+where
+    T: serde::Serialize + 'static,
+{
+    match surrealdb::sql::to_value(value) {
+        Ok(Value::Object(object)) => Ok(object.0),
+        Ok(other) => Err(DatabaseError::SerializationError(format!(
+            "expected an object, got `{}`",
+            other
+        ))),
+        Err(err) => Err(DatabaseError::SerializationError(err.to_string())),
+    }
+}
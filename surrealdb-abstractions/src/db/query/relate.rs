@@ -1,29 +1,47 @@
-use crate::db::query::query_parts::content;
+use crate::db::query::query_parts::{content, return_type, validate_table};
 use crate::prelude::query::ContentType;
 use crate::prelude::*;
-use crate::{impl_content, impl_only, impl_parallel, impl_relation, impl_timeout};
+use crate::{impl_content, impl_only, impl_parallel, impl_relation, impl_return, impl_timeout};
 use log::debug;
 
 pub struct Relate;
 
 impl Statement for Relate {
     const STATEMENT: &'static str = "RELATE";
+    type State = MissingRelation;
 }
 
-impl QueryBuilder<Relate> {
+/// # Examples
+///
+/// A relation must be set before the statement can be constructed:
+///
+/// ```compile_fail
+/// # use surrealdb_abstraction::db::{Query, Relate, Statement};
+/// let query = Relate::query("likes").try_construct()?;
+/// # Ok::<_, surrealdb_abstraction::db::QueryBuildError>(())
+/// ```
+impl<State> QueryBuilder<Relate, State>
+where
+    State: BuilderState,
+{
     impl_timeout!();
     impl_only!();
     impl_parallel!();
     impl_content!();
-    impl_relation!();
+    impl_relation!(Relate);
+    impl_return!();
 }
 
 impl Query for QueryBuilder<Relate> {
-    fn construct(self) -> String {
+    fn construct_with(self, bindings: &mut Bindings) -> Result<String, QueryBuildError> {
+        validate_table(&self.table)?;
+
         let (from, to) = if let Some((from, to)) = self.relation {
             (from, to)
         } else {
-            panic!("A relation must be specified!")
+            return Err(QueryBuildError::MissingRelation {
+                statement: Self::STATEMENT,
+            });
         };
 
         let only = if self.only { " ONLY" } else { "" };
@@ -32,12 +50,14 @@ impl Query for QueryBuilder<Relate> {
             "{}{} {}->{}->{}",
             Self::STATEMENT,
             only,
-            from,
+            bindings.bind(from),
             self.table,
-            to
+            bindings.bind(to)
         );
 
-        content(ContentType::Content, self.content, &mut query);
+        content(ContentType::Content, self.content, &mut query, bindings)?;
+
+        return_type(self.return_type, &mut query)?;
 
         if let Some((duration, unit)) = self.timeout {
             query.push_str(format!(" TIMEOUT {}{}", duration, unit).as_str());
@@ -49,6 +69,6 @@ impl Query for QueryBuilder<Relate> {
 
         debug!("Constructed query: {}", query);
 
-        query
+        Ok(query)
     }
 }
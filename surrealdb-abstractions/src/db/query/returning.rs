@@ -0,0 +1,56 @@
+use crate::db::FieldPath;
+use std::fmt::{Display, Formatter};
+
+/// What a mutating statement should return (`RETURN ...`).
+///
+/// The result of each statement is a list with one entry per affected record, so run it with
+/// `Vec<_>` of the type matching the choice, or with
+/// [`execute`](crate::db::QueryBuilder::execute) for `RETURN NONE`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Return {
+    /// `RETURN NONE`, nothing is returned.
+    None,
+    /// `RETURN BEFORE`, the records as they were before the statement.
+    Before,
+    /// `RETURN AFTER`, the records as they are after the statement.
+    After,
+    /// `RETURN DIFF`, a list of JSON Patch operations per record.
+    Diff,
+    /// `RETURN field, field, ...`, objects with only these fields.
+    Fields(Vec<FieldPath>),
+    /// `RETURN VALUE expr`, the bare value of `expr` per record.
+    Value(FieldPath),
+}
+
+impl Return {
+    /// `RETURN field, field, ...`
+    pub fn fields<F>(fields: impl IntoIterator<Item = F>) -> Self
+    where
+        F: Into<FieldPath>,
+    {
+        Return::Fields(fields.into_iter().map(Into::into).collect())
+    }
+
+    /// `RETURN VALUE expr`
+    pub fn value(expr: impl Into<FieldPath>) -> Self {
+        Return::Value(expr.into())
+    }
+}
+
+impl Display for Return {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str("RETURN ")?;
+
+        match self {
+            Return::None => f.write_str("NONE"),
+            Return::Before => f.write_str("BEFORE"),
+            Return::After => f.write_str("AFTER"),
+            Return::Diff => f.write_str("DIFF"),
+            Return::Fields(fields) => {
+                let fields = fields.iter().map(ToString::to_string).collect::<Vec<_>>();
+                f.write_str(&fields.join(", "))
+            }
+            Return::Value(expr) => write!(f, "VALUE {}", expr),
+        }
+    }
+}
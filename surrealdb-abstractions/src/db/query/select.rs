@@ -1,16 +1,27 @@
 use super::*;
+use crate::db::query::query_parts::{field_list, validate_table};
 use crate::db::query::traits::Query;
+use crate::db::QueryBuildError;
 use crate::{
     impl_fetch_fields, impl_fields, impl_filter, impl_group_all, impl_group_by, impl_limit,
-    impl_omit_fields, impl_only, impl_order_by, impl_parallel, impl_start,
+    impl_omit_fields, impl_only, impl_order_by, impl_parallel, impl_start, impl_target,
 };
 use log::debug;
 
-#[derive(Clone)]
+#[derive(Clone, Debug)]
 pub struct Select;
 
 impl Statement for Select {
     const STATEMENT: &'static str = "SELECT";
+    type State = Ready;
+}
+
+impl Select {
+    /// A `SELECT` reading from `target`, like a record range, graph traversal or subquery,
+    /// instead of a table.
+    pub fn from(target: impl Into<Target>) -> QueryBuilder<Select> {
+        QueryBuilder::<Select>::new("").set_from(target)
+    }
 }
 
 impl QueryBuilder<Select> {
@@ -25,10 +36,14 @@ impl QueryBuilder<Select> {
     impl_only!();
     impl_fetch_fields!();
     impl_parallel!();
+    impl_target!();
 }
 
 impl Query for QueryBuilder<Select> {
-    fn construct(self) -> String {
+    fn construct_with(self, bindings: &mut Bindings) -> Result<String, QueryBuildError> {
+        if self.from.is_none() {
+            validate_table(&self.table)?;
+        }
         let mut query = String::new();
 
         // Start with the query type
@@ -36,26 +51,43 @@ impl Query for QueryBuilder<Select> {
         query.push(' ');
 
         // Fields to select
-        let fields = if let Some(fields) = self.fields {
-            fields
-                .into_iter()
-                .map(|(field, alias)| {
-                    if let Some(alias) = alias {
-                        format!("{} AS {}", field, alias)
-                    } else {
-                        field
+        let mut fields = self
+            .fields
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(field, alias)| {
+                field.validate()?;
+
+                match alias {
+                    Some(alias) => {
+                        alias.validate()?;
+                        Ok(format!("{} AS {}", field, alias))
                     }
-                })
-                .collect::<Vec<_>>()
-                .join(", ")
-        } else {
-            "*".to_string()
-        };
+                    None => Ok(field.to_string()),
+                }
+            })
+            .collect::<Result<Vec<_>, QueryBuildError>>()?;
+
+        for (expression, alias) in self.expressions.unwrap_or_default() {
+            let expression = expression.construct_with(bindings)?;
+
+            match alias {
+                Some(alias) => {
+                    alias.validate()?;
+                    fields.push(format!("{} AS {}", expression, alias));
+                }
+                None => fields.push(expression),
+            }
+        }
 
-        query.push_str(&fields);
+        if fields.is_empty() {
+            query.push('*');
+        } else {
+            query.push_str(&fields.join(", "));
+        }
 
         if let Some(omit_fields) = self.omit_fields {
-            query.push_str(format!(" OMIT {}", omit_fields.join(", ")).as_str());
+            query.push_str(format!(" OMIT {}", field_list(&omit_fields)?).as_str());
         }
 
         // From which table
@@ -65,10 +97,15 @@ impl Query for QueryBuilder<Select> {
             query.push_str(" ONLY");
         }
 
-        query.push_str(format!(" {} ", self.table).as_str());
+        let from = match self.from {
+            Some(from) => from.construct_with(bindings)?,
+            None => self.table.to_string(),
+        };
+
+        query.push_str(format!(" {} ", from).as_str());
 
         // Add WHERE clause if filter is not empty
-        let filter_clause = self.filter.construct();
+        let filter_clause = self.filter.construct_with(bindings)?;
         if !filter_clause.is_empty() {
             query.push_str(&filter_clause);
         }
@@ -77,11 +114,16 @@ impl Query for QueryBuilder<Select> {
         if self.group_all {
             query.push_str(" GROUP ALL");
         } else if let Some(group_by) = self.group_by {
-            query.push_str(format!(" GROUP BY {}", group_by.join(", ")).as_str());
+            query.push_str(format!(" GROUP BY {}", field_list(&group_by)?).as_str());
         }
 
         // Order By clause
         if let Some(order) = self.order {
+            let order = order
+                .iter()
+                .map(|order| order.validate().map(|_| order.to_string()))
+                .collect::<Result<Vec<_>, QueryBuildError>>()?;
+
             query.push_str(format!(" ORDER BY {}", order.join(", ")).as_str());
         }
 
@@ -97,6 +139,6 @@ impl Query for QueryBuilder<Select> {
 
         debug!("Constructed SQL: {}", query);
 
-        query
+        Ok(query)
     }
 }
@@ -0,0 +1,29 @@
+mod sealed {
+    pub trait Sealed {}
+}
+
+/// How complete a [`QueryBuilder`](crate::db::QueryBuilder) is.
+///
+/// Only a builder in the [`Ready`] state implements [`Query`](crate::db::Query), so a statement
+/// that is missing a required part cannot be constructed or run.
+pub trait BuilderState: sealed::Sealed {}
+
+/// The builder has everything the statement needs.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Ready;
+
+/// A `RELATE` builder without a relation, call `relate_items` to complete it.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct MissingRelation;
+
+/// An `UPDATE` or `UPSERT` builder without content, set content to complete it.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct MissingContent;
+
+impl sealed::Sealed for Ready {}
+impl sealed::Sealed for MissingRelation {}
+impl sealed::Sealed for MissingContent {}
+
+impl BuilderState for Ready {}
+impl BuilderState for MissingRelation {}
+impl BuilderState for MissingContent {}
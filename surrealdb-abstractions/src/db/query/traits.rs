@@ -1,9 +1,12 @@
-use crate::db::QueryBuilder;
+use crate::db::{Bindings, BoundQuery, BuilderState, Ident, QueryBuildError, QueryBuilder};
 
 pub trait Statement {
     const STATEMENT: &'static str;
 
-    fn query(table: &str) -> QueryBuilder<Self>
+    /// The state a new builder for this statement starts in.
+    type State: BuilderState;
+
+    fn query(table: impl Into<Ident>) -> QueryBuilder<Self, Self::State>
     where
         Self: Sized,
     {
@@ -12,17 +15,62 @@ pub trait Statement {
 }
 
 pub trait Query {
-    fn construct(self) -> String;
+    /// Constructs the query text with every value replaced by a placeholder bound in
+    /// `bindings`, or returns why the query is invalid.
+    ///
+    /// Statements sent together, like the ones of a [`Batch`](crate::db::Batch) or a subquery,
+    /// share one `bindings`, so their placeholders never collide.
+    fn construct_with(self, bindings: &mut Bindings) -> Result<String, QueryBuildError>;
+
+    /// Constructs the query text, with every value replaced by a `$pN` placeholder, and the
+    /// bindings for those placeholders, or returns why the query is invalid.
+    fn try_construct(self) -> Result<BoundQuery, QueryBuildError>
+    where
+        Self: Sized,
+    {
+        let mut bindings = Bindings::default();
+        let query = self.construct_with(&mut bindings)?;
+
+        Ok(BoundQuery::new(query, bindings))
+    }
+
+    /// Constructs the query. Prefer [`Query::try_construct`], which returns the error instead.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the query is invalid, e.g. it has an empty table name, an empty field name or
+    /// one with control characters, or a custom operator that is not made of operator symbols.
+    fn construct(self) -> BoundQuery
+    where
+        Self: Sized,
+    {
+        match self.try_construct() {
+            Ok(query) => query,
+            Err(err) => panic!("{}", err),
+        }
+    }
+}
+
+/// Moves the bindings into the shared ones, renaming the placeholders.
+impl Query for BoundQuery {
+    fn construct_with(self, bindings: &mut Bindings) -> Result<String, QueryBuildError> {
+        bindings.merge(self)
+    }
+
+    fn try_construct(self) -> Result<BoundQuery, QueryBuildError> {
+        Ok(self)
+    }
 }
 
+/// A raw statement, which must not use the `$pN` placeholder names of bound values.
 impl Query for String {
-    fn construct(self) -> String {
-        self
+    fn construct_with(self, bindings: &mut Bindings) -> Result<String, QueryBuildError> {
+        bindings.merge(BoundQuery::new(self, Bindings::default()))
     }
 }
 
 impl Query for &str {
-    fn construct(self) -> String {
-        self.to_string()
+    fn construct_with(self, bindings: &mut Bindings) -> Result<String, QueryBuildError> {
+        self.to_string().construct_with(bindings)
     }
 }
@@ -1,26 +1,53 @@
-use crate::db::query::query_parts::{content, filter, parallel, timeout};
+use crate::db::query::query_parts::{filter, parallel, return_type, timeout, validate_table};
 use crate::db::query::traits::Statement;
 use crate::db::query::ContentType;
-use crate::db::{Query, QueryBuilder};
-use crate::{impl_content, impl_filter, impl_only, impl_parallel, impl_timeout};
+use crate::db::{Bindings, Query, QueryBuildError, QueryBuilder};
+use crate::db::{BuilderState, MissingContent};
+use crate::{
+    impl_content, impl_data, impl_filter, impl_only, impl_parallel, impl_return, impl_target,
+    impl_timeout,
+};
 
 pub struct Update;
 
 impl Statement for Update {
     const STATEMENT: &'static str = "UPDATE";
+    type State = MissingContent;
 }
 
-impl QueryBuilder<Update> {
-    impl_content!();
+/// # Examples
+///
+/// Content must be set before the statement can be constructed:
+///
+/// ```compile_fail
+/// # use surrealdb_abstraction::db::{Query, Update, Statement};
+/// let query = Update::query("person").try_construct()?;
+/// # Ok::<_, surrealdb_abstraction::db::QueryBuildError>(())
+/// ```
+impl<State> QueryBuilder<Update, State>
+where
+    State: BuilderState,
+{
+    impl_content!(Update);
+    impl_data!(Update);
     impl_only!();
     impl_parallel!();
     impl_filter!();
     impl_timeout!();
+    impl_return!();
+    impl_target!();
 }
 
 impl Query for QueryBuilder<Update> {
     #[inline]
-    fn construct(self) -> String {
+    fn construct_with(mut self, bindings: &mut Bindings) -> Result<String, QueryBuildError> {
+        let target = match self.from.take() {
+            Some(from) => from.construct_with(bindings)?,
+            None => {
+                validate_table(&self.table)?;
+                self.table.to_string()
+            }
+        };
         let mut query = String::new();
 
         query.push_str(Self::STATEMENT);
@@ -29,21 +56,19 @@ impl Query for QueryBuilder<Update> {
             query.push_str(" ONLY");
         }
 
-        query.push_str(" ");
-        query.push_str(&self.table);
+        query.push(' ');
+        query.push_str(&target);
 
-        if self.content.is_none() {
-            panic!("Content must be specified!");
-        }
+        self.data_clause(ContentType::Merge, &mut query, bindings)?;
 
-        content(ContentType::Merge, self.content, &mut query);
+        filter(self.filter, &mut query, bindings)?;
 
-        filter(self.filter, &mut query);
+        return_type(self.return_type, &mut query)?;
 
         timeout(self.timeout, &mut query);
 
         parallel(self.parallel, &mut query);
 
-        query
+        Ok(query)
     }
 }
@@ -1,18 +1,23 @@
-use crate::db::query::query_parts::{content, filter, parallel, timeout};
+use crate::db::query::query_parts::{filter, parallel, return_type, timeout, validate_table};
 use crate::db::query::traits::Statement;
 use crate::db::query::ContentType;
-use crate::db::QueryBuilder;
+use crate::db::{Bindings, QueryBuildError, QueryBuilder};
+use crate::db::{BuilderState, MissingContent};
 use crate::prelude::Query;
-use crate::{impl_content, impl_filter, impl_only, impl_parallel, impl_timeout};
+use crate::{
+    impl_content, impl_data, impl_filter, impl_only, impl_parallel, impl_return, impl_timeout,
+};
 
 pub struct Upsert;
 
 impl Statement for Upsert {
     const STATEMENT: &'static str = "UPSERT";
+    type State = MissingContent;
 }
 
 impl Query for QueryBuilder<Upsert> {
-    fn construct(self) -> String {
+    fn construct_with(mut self, bindings: &mut Bindings) -> Result<String, QueryBuildError> {
+        validate_table(&self.table)?;
         let mut query = String::new();
         query.push_str(Self::STATEMENT);
         query.push(' ');
@@ -21,30 +26,40 @@ impl Query for QueryBuilder<Upsert> {
             query.push_str("ONLY ");
         }
 
-        query.push_str(&self.table);
+        query.push_str(&self.table.to_string());
 
-        query.push(' ');
-
-        if self.content.is_none() {
-            panic!("Content must be specified!")
-        }
+        self.data_clause(ContentType::Content, &mut query, bindings)?;
 
-        content(ContentType::Content, self.content, &mut query);
+        filter(self.filter, &mut query, bindings)?;
 
-        filter(self.filter, &mut query);
+        return_type(self.return_type, &mut query)?;
 
         timeout(self.timeout, &mut query);
 
         parallel(self.parallel, &mut query);
 
-        query
+        Ok(query)
     }
 }
 
-impl QueryBuilder<Upsert> {
+/// # Examples
+///
+/// Content must be set before the statement can be constructed:
+///
+/// ```compile_fail
+/// # use surrealdb_abstraction::db::{Query, Upsert, Statement};
+/// let query = Upsert::query("person").try_construct()?;
+/// # Ok::<_, surrealdb_abstraction::db::QueryBuildError>(())
+/// ```
+impl<State> QueryBuilder<Upsert, State>
+where
+    State: BuilderState,
+{
     impl_only!();
     impl_parallel!();
     impl_timeout!();
-    impl_content!();
+    impl_content!(Upsert);
+    impl_data!(Upsert);
     impl_filter!();
+    impl_return!();
 }
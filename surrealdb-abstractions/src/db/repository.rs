@@ -0,0 +1,200 @@
+use crate::db::error::DatabaseError;
+use crate::db::{
+    ContentOptions, ContentType, Delete, Executor, FieldPath, Filter, OrderBy, Return, Select,
+    Statement, SurrealTable, Target, Update,
+};
+use crate::Result;
+use futures::Stream;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::marker::PhantomData;
+use surrealdb::sql::{Id, Thing};
+
+/// CRUD operations on the records of a [`SurrealTable`], run on any [`Executor`].
+///
+/// # Examples
+///
+/// ```no_run
+/// # use std::sync::Arc;
+/// # use serde::{Deserialize, Serialize};
+/// # use surrealdb::engine::local::Db;
+/// # use surrealdb::sql::Thing;
+/// # use surrealdb::Surreal;
+/// # use surrealdb_abstraction::db::{Repository, SurrealTable};
+/// #[derive(Clone, Serialize, Deserialize, SurrealTable)]
+/// struct User {
+///     id: Option<Thing>,
+///     name: String,
+///     age: u32,
+/// }
+///
+/// # async fn example(db: Arc<Surreal<Db>>) -> surrealdb_abstraction::Result<()> {
+/// let users = Repository::<User, _>::new(db);
+///
+/// let alice = users
+///     .insert(&User { id: None, name: "Alice".to_string(), age: 30 })
+///     .await?;
+/// let adults = users.find_many(UserFields::AGE.gte(18)).await?;
+/// let count = users.count(UserFields::AGE.lt(18)).await?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct Repository<T, E>
+where
+    E: Executor,
+{
+    db: E,
+    entity: PhantomData<fn() -> T>,
+}
+
+impl<T, E> Clone for Repository<T, E>
+where
+    E: Executor + Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            db: self.db.clone(),
+            entity: PhantomData,
+        }
+    }
+}
+
+impl<T, E> Repository<T, E>
+where
+    T: SurrealTable + DeserializeOwned + 'static,
+    E: Executor,
+{
+    pub fn new(db: E) -> Self {
+        Self {
+            db,
+            entity: PhantomData,
+        }
+    }
+
+    pub fn db(&self) -> &E {
+        &self.db
+    }
+
+    /// The id of the record `id` of the table, e.g. `user:alice`.
+    pub fn thing(id: impl Into<Id>) -> Thing {
+        Thing::from((T::TABLE, id.into()))
+    }
+
+    pub async fn find_by_id(&self, id: impl Into<Id>) -> Result<Option<T>> {
+        let records: Vec<T> = Select::query(T::TABLE)
+            .set_from(Self::thing(id))
+            .run(&self.db, 0)
+            .await?;
+
+        Ok(records.into_iter().next())
+    }
+
+    /// The records matching `filter`, which can also be a single [`Condition`](crate::db::Condition).
+    pub async fn find_many(&self, filter: impl Into<Filter>) -> Result<Vec<T>> {
+        Select::query(T::TABLE)
+            .set_filter(filter.into())
+            .run(&self.db, 0)
+            .await
+    }
+
+    /// Creates `entity`, returning the stored record with its id.
+    pub async fn insert(&self, entity: &T) -> Result<T> {
+        let records: Vec<T> = entity.create_query()?.run(&self.db, 0).await?;
+
+        records
+            .into_iter()
+            .next()
+            .ok_or_else(|| DatabaseError::EmptyResponse("CREATE").into())
+    }
+
+    /// Replaces the stored record with `entity`, which must have an id. Returns `None` if there
+    /// is no record with that id.
+    pub async fn update(&self, entity: &T) -> Result<Option<T>> {
+        let records: Vec<T> = entity
+            .update_query()?
+            .set_content_type(ContentType::Content)
+            .run(&self.db, 0)
+            .await?;
+
+        Ok(records.into_iter().next())
+    }
+
+    /// Merges the fields of `patch` into the record `id`, leaving its other fields as they are.
+    /// Returns `None` if there is no record with that id.
+    pub async fn merge<P>(&self, id: impl Into<Id>, patch: &P) -> Result<Option<T>>
+    where
+        P: Serialize + Clone + 'static,
+    {
+        let records: Vec<T> = Update::query(T::TABLE)
+            .set_from(Self::thing(id))
+            .set_content_from_with(patch, ContentOptions::new().skip_id())?
+            .set_content_type(ContentType::Merge)
+            .run(&self.db, 0)
+            .await?;
+
+        Ok(records.into_iter().next())
+    }
+
+    /// Deletes the record `id`, returning it, or `None` if there was no record with that id.
+    pub async fn delete(&self, id: impl Into<Id>) -> Result<Option<T>> {
+        let records: Vec<T> = Delete::query(T::TABLE)
+            .set_from(Self::thing(id))
+            .set_return(Return::Before)
+            .run(&self.db, 0)
+            .await?;
+
+        Ok(records.into_iter().next())
+    }
+
+    /// The number of records matching `filter`.
+    pub async fn count(&self, filter: impl Into<Filter>) -> Result<u64> {
+        #[derive(serde::Deserialize)]
+        struct Count {
+            count: u64,
+        }
+
+        let counts: Vec<Count> = Select::query(T::TABLE)
+            .add_field(FieldPath::raw("count()"), Some("count"))
+            .set_filter(filter.into())
+            .group_all()
+            .run(&self.db, 0)
+            .await?;
+
+        Ok(counts.first().map_or(0, |count| count.count))
+    }
+
+    pub async fn exists(&self, id: impl Into<Id>) -> Result<bool> {
+        #[derive(serde::Deserialize)]
+        struct Record {
+            #[allow(dead_code)]
+            id: Thing,
+        }
+
+        let ids: Vec<Record> = Select::query(T::TABLE)
+            .add_field("id", None)
+            .set_from(Target::Record(Self::thing(id)))
+            .run(&self.db, 0)
+            .await?;
+
+        Ok(!ids.is_empty())
+    }
+
+    /// Streams the records matching `filter` page by page, see
+    /// [`Paginator`](crate::db::Paginator).
+    pub fn paginate<O>(
+        &self,
+        filter: impl Into<Filter>,
+        order: impl IntoIterator<Item = O>,
+        page_size: u64,
+    ) -> impl Stream<Item = Result<Vec<T>>>
+    where
+        O: Into<OrderBy>,
+        E: Clone,
+    {
+        Select::query(T::TABLE)
+            .set_filter(filter.into())
+            .order_by(order)
+            .paginate(page_size)
+            .pages(self.db.clone())
+    }
+}
@@ -0,0 +1,195 @@
+use crate::db::{BoundQuery, DatabaseError, ErrorKind, Executor, QueryObserver};
+use rand::Rng;
+use std::time::Duration;
+use surrealdb::Surreal;
+
+/// How often and how long to wait before running a query again after it failed.
+///
+/// A query is retried if its error is of one of the retryable [`ErrorKind`]s, only
+/// [`TransactionConflict`](ErrorKind::TransactionConflict) by default. The wait grows
+/// exponentially from the initial backoff up to the maximum, and with jitter is a random duration
+/// between half of it and all of it, so conflicting writers do not retry in lockstep.
+///
+/// [`ConnectionLost`](ErrorKind::ConnectionLost) is not retried unless added with
+/// [`retry_on`](Self::retry_on): the connection can drop after the server applied the query, so
+/// retrying is only safe for idempotent queries.
+///
+/// Set it for a single statement or [`Transaction`](crate::db::Transaction) with `with_retry`, or
+/// for every one run on a connection with [`Retrying`]. A [`Batch`](crate::db::Batch) is never
+/// retried, as it is not atomic.
+///
+/// # Examples
+///
+/// ```
+/// # use std::time::Duration;
+/// # use surrealdb_abstraction::db::{ErrorKind, RetryPolicy};
+///
+/// let policy = RetryPolicy::new(5)
+///     .backoff(Duration::from_millis(10), Duration::from_secs(1))
+///     .jitter(false);
+///
+/// assert_eq!(policy.delay(1), Duration::from_millis(10));
+/// assert_eq!(policy.delay(3), Duration::from_millis(40));
+/// assert_eq!(policy.delay(10), Duration::from_secs(1));
+/// assert!(policy.retries(ErrorKind::TransactionConflict));
+/// assert!(!policy.retries(ErrorKind::RecordExists));
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct RetryPolicy {
+    max_attempts: u32,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+    multiplier: f64,
+    jitter: bool,
+    retryable: Vec<ErrorKind>,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            initial_backoff: Duration::from_millis(50),
+            max_backoff: Duration::from_secs(2),
+            multiplier: 2.0,
+            jitter: true,
+            retryable: vec![ErrorKind::TransactionConflict],
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Runs a query at most `max_attempts` times, including the first attempt.
+    pub fn new(max_attempts: u32) -> Self {
+        Self {
+            max_attempts,
+            ..Self::default()
+        }
+    }
+
+    /// The wait after the first failed attempt and the longest wait.
+    pub fn backoff(mut self, initial: Duration, max: Duration) -> Self {
+        self.initial_backoff = initial;
+        self.max_backoff = max;
+
+        self
+    }
+
+    /// How much the wait grows after every failed attempt, 2 by default.
+    pub fn multiplier(mut self, multiplier: f64) -> Self {
+        self.multiplier = multiplier;
+
+        self
+    }
+
+    pub fn jitter(mut self, jitter: bool) -> Self {
+        self.jitter = jitter;
+
+        self
+    }
+
+    /// Replaces the kinds of errors that are retried.
+    pub fn retry_on(mut self, kinds: impl IntoIterator<Item = ErrorKind>) -> Self {
+        self.retryable = kinds.into_iter().collect();
+
+        self
+    }
+
+    pub fn max_attempts(&self) -> u32 {
+        self.max_attempts
+    }
+
+    /// Whether errors of `kind` are retried.
+    pub fn retries(&self, kind: ErrorKind) -> bool {
+        self.retryable.contains(&kind)
+    }
+
+    /// Whether to run the query again after `attempt` attempts failed, the last one with `err`.
+    pub fn should_retry(&self, err: &DatabaseError, attempt: u32) -> bool {
+        attempt < self.max_attempts && self.retries(err.kind())
+    }
+
+    /// How long to wait after `attempt` attempts failed, without jitter.
+    pub fn delay(&self, attempt: u32) -> Duration {
+        let exponent = attempt.saturating_sub(1).min(i32::MAX as u32) as i32;
+        let delay = self.initial_backoff.as_secs_f64() * self.multiplier.powi(exponent);
+
+        if delay.is_finite() && delay < self.max_backoff.as_secs_f64() {
+            Duration::from_secs_f64(delay.max(0.0))
+        } else {
+            self.max_backoff
+        }
+    }
+
+    /// How long to wait after `attempt` attempts failed, with jitter if it is enabled.
+    pub(crate) fn wait(&self, attempt: u32) -> Duration {
+        let delay = self.delay(attempt);
+
+        if self.jitter && !delay.is_zero() {
+            rand::thread_rng().gen_range(delay / 2..=delay)
+        } else {
+            delay
+        }
+    }
+}
+
+/// An [`Executor`] that retries every statement and transaction run on it with a
+/// [`RetryPolicy`], unless it sets its own with `with_retry`.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use surrealdb::engine::local::Db;
+/// # use surrealdb::Surreal;
+/// # use surrealdb_abstraction::db::{Retrying, RetryPolicy, Statement, Upsert};
+/// # async fn example(db: Surreal<Db>) -> surrealdb_abstraction::Result<()> {
+/// let db = Retrying::new(db, RetryPolicy::new(5));
+///
+/// Upsert::query("counter")
+///     .add_field_to_content("hits", 1)
+///     .execute(&db)
+///     .await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct Retrying<E> {
+    executor: E,
+    policy: RetryPolicy,
+}
+
+impl<E> Retrying<E> {
+    pub fn new(executor: E, policy: RetryPolicy) -> Self {
+        Self { executor, policy }
+    }
+
+    pub fn policy(&self) -> &RetryPolicy {
+        &self.policy
+    }
+
+    pub fn into_inner(self) -> E {
+        self.executor
+    }
+}
+
+impl<E> Executor for Retrying<E>
+where
+    E: Executor,
+{
+    type Connection = E::Connection;
+
+    fn connection(&self) -> &Surreal<Self::Connection> {
+        self.executor.connection()
+    }
+
+    fn prepare(&self, query: BoundQuery) -> BoundQuery {
+        self.executor.prepare(query)
+    }
+
+    fn retry_policy(&self) -> Option<&RetryPolicy> {
+        Some(&self.policy)
+    }
+
+    fn observer(&self) -> Option<&dyn QueryObserver> {
+        self.executor.observer()
+    }
+}
@@ -1,12 +1,15 @@
 use super::error::DatabaseError;
+use crate::db::observer::{returned_rows, total_rows};
 use crate::db::query::traits::{Query, Statement};
-use crate::db::QueryBuilder;
+use crate::db::{BoundQuery, BuilderState, Executor, QueryBuilder, QueryInfo, RetryPolicy};
 use crate::{InternalResult, Result};
-use log::error;
+use log::{error, warn};
 use once_cell::sync::Lazy;
-use std::sync::Arc;
-use surrealdb::opt::QueryResult;
-use surrealdb::{Connection, Surreal};
+use std::any::Any;
+use std::future::Future;
+use std::time::Instant;
+use surrealdb::sql::{from_value, Object, Value};
+use surrealdb::{Connection, Response, Surreal};
 
 pub(crate) struct Runner;
 
@@ -15,95 +18,283 @@ impl Runner {
         Self
     }
 
-    fn handle_response<U>(
-        mut res: surrealdb::Response,
-        index: impl QueryResult<U>,
-    ) -> InternalResult<U>
-    where
-        U: serde::de::DeserializeOwned,
-    {
-        let data: U = match res.take(index) {
-            Ok(data) => data,
+    /// Takes the raw result of every statement of `res`, failing with the first error.
+    pub(crate) fn take_all(mut res: Response) -> InternalResult<Vec<surrealdb::Value>> {
+        (0..res.num_statements())
+            .map(|index| Self::take(&mut res, index))
+            .collect()
+    }
+
+    fn take(res: &mut Response, index: usize) -> InternalResult<surrealdb::Value> {
+        match res.take(index) {
+            Ok(value) => Ok(value),
             Err(err) => {
                 error!("Error running query: {}", err);
-                return Err(DatabaseError::ResponseError(err));
+                Err(DatabaseError::from(err))
             }
+        }
+    }
+
+    /// Deserializes the raw result of a statement like [`Response::take`] with a statement
+    /// index does: a single record from an array of at most one, and an array from a single
+    /// record.
+    pub(crate) fn from_value<U>(value: surrealdb::Value) -> InternalResult<U>
+    where
+        U: serde::de::DeserializeOwned + 'static,
+    {
+        // A raw value does not deserialize from itself, so it is returned as is.
+        let mut value = Some(value);
+        if let Some(raw) = (&mut value as &mut dyn Any).downcast_mut::<Option<U>>() {
+            return Ok(raw.take().expect("the value is only taken here"));
+        }
+
+        let value = value.map(surrealdb::Value::into_inner).unwrap_or_default();
+        let fallback = match &value {
+            Value::Array(rows) if rows.len() > 1 => None,
+            Value::Array(rows) => Some(rows.first().cloned().unwrap_or_default()),
+            record => Some(Value::from(vec![record.clone()])),
         };
 
-        Ok(data)
+        match from_value(value) {
+            Ok(data) => Ok(data),
+            Err(err) => match fallback.map(from_value) {
+                Some(Ok(data)) => Ok(data),
+                _ => {
+                    error!("Error deserializing query result: {}", err);
+                    Err(DatabaseError::from(surrealdb::Error::Db(err)))
+                }
+            },
+        }
     }
 
-    pub(crate) async fn run<C, U, Q>(
-        db: &Arc<Surreal<C>>,
-        query: Q,
-        index: impl QueryResult<U>,
-    ) -> InternalResult<U>
+    /// Constructs `query`, logging why if it is invalid.
+    pub(crate) fn construct<Q>(query: Q) -> InternalResult<BoundQuery>
     where
-        C: Connection,
-        U: serde::de::DeserializeOwned,
         Q: Query,
     {
-        let query = query.construct();
+        match query.try_construct() {
+            Ok(query) => Ok(query),
+            Err(err) => {
+                error!("Error constructing query: {}", err);
+                Err(DatabaseError::QueryBuildError(err))
+            }
+        }
+    }
+
+    /// Constructs `query` and sends it with its bindings, returning the raw response.
+    pub(crate) async fn send<E, Q>(db: &E, query: Q) -> InternalResult<Response>
+    where
+        E: Executor + ?Sized,
+        Q: Query,
+    {
+        let query = Self::construct(query)?;
+        let BoundQuery { query, bindings } = db.prepare(query);
 
-        let res = match db.query(&query).await {
-            Ok(res) => res,
+        match db
+            .connection()
+            .query(&query)
+            .bind(Object::from(bindings))
+            .await
+        {
+            Ok(res) => Ok(res),
             Err(err) => {
                 error!("Error running query: {} with error: {}", query, err);
-                return Err(DatabaseError::TransactionError(err));
+                Err(DatabaseError::classify(
+                    err,
+                    DatabaseError::TransactionError,
+                ))
             }
-        };
+        }
+    }
+
+    /// Sends `query` and only checks that it succeeded.
+    pub(crate) async fn execute<E, Q>(db: &E, query: Q, table: Option<&str>) -> InternalResult<()>
+    where
+        E: Executor + ?Sized,
+        Q: Query,
+    {
+        let query = Self::construct(query)?;
+        let info = QueryInfo::new(&query, table.map(ToString::to_string));
+
+        let attempt = async { Self::take_all(Self::send(db, query).await?) };
 
-        Self::handle_response(res, index)
+        Self::observe(db, &info, attempt, |values| total_rows(values))
+            .await
+            .map(|_| ())
     }
 
-    pub(crate) async fn run_lazy<C, U, Q>(
-        db: &Lazy<Surreal<C>>,
+    /// Sends `query` and takes the raw result at `index`, counting its records for the observer.
+    pub(crate) async fn run<E, Q>(
+        db: &E,
         query: Q,
-        index: impl QueryResult<U>,
-    ) -> InternalResult<U>
+        table: Option<&str>,
+        index: usize,
+    ) -> InternalResult<surrealdb::Value>
     where
-        C: Connection,
-        U: serde::de::DeserializeOwned,
+        E: Executor + ?Sized,
         Q: Query,
     {
-        let query = query.construct();
+        let query = Self::construct(query)?;
+        let info = QueryInfo::new(&query, table.map(ToString::to_string));
 
-        let res = match db.query(&query).await {
-            Ok(res) => res,
+        let attempt = async {
+            let mut res = Self::send(db, query).await?;
+
+            Self::take(&mut res, index)
+        };
+
+        Self::observe(db, &info, attempt, returned_rows).await
+    }
+
+    /// Runs `attempt`, reporting it to the executor's [`QueryObserver`](crate::db::QueryObserver)
+    /// and, with the `tracing` feature, recording it in a span. `rows` counts the records of a
+    /// successful result.
+    pub(crate) async fn observe<E, T, Fut>(
+        db: &E,
+        info: &QueryInfo,
+        attempt: Fut,
+        rows: impl FnOnce(&T) -> Option<u64>,
+    ) -> InternalResult<T>
+    where
+        E: Executor + ?Sized,
+        Fut: Future<Output = InternalResult<T>>,
+    {
+        #[cfg(feature = "tracing")]
+        let span = info.span();
+        #[cfg(feature = "tracing")]
+        let attempt = tracing::Instrument::instrument(attempt, span.clone());
+
+        let observer = db.observer();
+
+        if let Some(observer) = observer {
+            observer.before_execute(info);
+        }
+
+        let start = Instant::now();
+        let res = attempt.await;
+        let duration = start.elapsed();
+
+        match &res {
+            Ok(value) => {
+                let rows = rows(value);
+
+                #[cfg(feature = "tracing")]
+                if let Some(rows) = rows {
+                    span.record("db.response.returned_rows", rows);
+                }
+
+                if let Some(observer) = observer {
+                    observer.after_execute(info, duration, rows);
+                }
+            }
             Err(err) => {
-                error!("Error running query: {} with error: {}", query, err);
-                return Err(DatabaseError::TransactionError(err));
+                #[cfg(feature = "tracing")]
+                {
+                    span.record("error.type", err.kind().as_str());
+                    span.record("otel.status_code", "ERROR");
+                }
+
+                if let Some(observer) = observer {
+                    observer.on_error(info, duration, err);
+                }
             }
+        }
+
+        res
+    }
+
+    /// Calls `attempt` until it succeeds, fails with an error `policy` does not retry, or runs
+    /// out of attempts. Without a `policy` the executor's default is used.
+    pub(crate) async fn retry<E, T, F, Fut>(
+        db: &E,
+        policy: Option<&RetryPolicy>,
+        mut attempt: F,
+    ) -> InternalResult<T>
+    where
+        E: Executor + ?Sized,
+        F: FnMut() -> Fut,
+        Fut: Future<Output = InternalResult<T>>,
+    {
+        let Some(policy) = policy.or_else(|| db.retry_policy()) else {
+            return attempt().await;
         };
 
-        Self::handle_response(res, index)
+        let mut attempts = 1;
+
+        loop {
+            match attempt().await {
+                Err(err) if policy.should_retry(&err, attempts) => {
+                    let wait = policy.wait(attempts);
+
+                    warn!(
+                        "Retrying query in {:?} after attempt {} failed with error: {}",
+                        wait, attempts, err
+                    );
+
+                    tokio::time::sleep(wait).await;
+                    attempts += 1;
+                }
+                res => return res,
+            }
+        }
     }
 }
 
-impl<Type> QueryBuilder<Type>
+impl<Type, State> QueryBuilder<Type, State>
 where
     Self: Query,
     Type: Statement + ?Sized,
+    State: BuilderState,
 {
-    pub async fn run<C, U>(self, db: &Arc<Surreal<C>>, index: impl QueryResult<U>) -> Result<U>
+    /// Runs the statement on `db`, which can be anything holding a connection, see
+    /// [`Executor`], and takes the result of the statement at `index`.
+    ///
+    /// Like [`Response::take`], `U` can be a `Vec<T>` or an `Option<T>` of a single record, as
+    /// well as a [`surrealdb::Value`].
+    pub async fn run<E, U>(mut self, db: &E, index: usize) -> Result<U>
     where
-        C: Connection,
-        U: serde::de::DeserializeOwned,
+        E: Executor + ?Sized,
+        U: serde::de::DeserializeOwned + 'static,
     {
-        Runner::run(db, self, index).await.map_err(|e| e.into())
+        let retry = self.take_retry_internal();
+        let table = self.table_internal().map(ToString::to_string);
+        let query = Runner::construct(self)?;
+
+        Runner::retry(db, retry.as_ref(), || {
+            Runner::run(db, query.clone(), table.as_deref(), index)
+        })
+        .await
+        .and_then(Runner::from_value)
+        .map_err(|e| e.into())
     }
 
-    pub async fn run_lazy<C, U>(
-        self,
-        db: &Lazy<Surreal<C>>,
-        index: impl QueryResult<U>,
-    ) -> Result<U>
+    /// Runs the statement and discards its result, e.g. for `RETURN NONE`.
+    pub async fn execute<E>(mut self, db: &E) -> Result<()>
+    where
+        E: Executor + ?Sized,
+    {
+        let retry = self.take_retry_internal();
+        let table = self.table_internal().map(ToString::to_string);
+        let query = Runner::construct(self)?;
+
+        Runner::retry(db, retry.as_ref(), || {
+            Runner::execute(db, query.clone(), table.as_deref())
+        })
+        .await
+        .map_err(|e| e.into())
+    }
+
+    /// Retries the statement with `policy` when it fails, instead of the executor's default.
+    pub fn with_retry(self, policy: RetryPolicy) -> Self {
+        self.set_retry_internal(policy)
+    }
+
+    #[deprecated(note = "`run` accepts a `Lazy` as well")]
+    pub async fn run_lazy<C, U>(self, db: &Lazy<Surreal<C>>, index: usize) -> Result<U>
     where
         C: Connection,
-        U: serde::de::DeserializeOwned,
+        U: serde::de::DeserializeOwned + 'static,
     {
-        Runner::run_lazy(db, self, index)
-            .await
-            .map_err(|e| e.into())
+        self.run(db, index).await
     }
 }
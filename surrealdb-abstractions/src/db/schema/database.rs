@@ -0,0 +1,68 @@
+use crate::db::schema::{comment, impl_define, impl_execute, DefineMode};
+use crate::db::{Bindings, Ident, Query, QueryBuildError};
+use surrealdb::sql::Duration;
+
+/// `DEFINE DATABASE`, in the namespace of the connection.
+///
+/// # Examples
+///
+/// ```
+/// # use std::time::Duration;
+/// # use surrealdb_abstraction::db::{DefineDatabase, Query};
+///
+/// let query = DefineDatabase::new("shop")
+///     .changefeed(Duration::from_secs(3 * 24 * 60 * 60))
+///     .comment("Orders and customers")
+///     .try_construct()?;
+///
+/// assert_eq!(
+///     query.query,
+///     "DEFINE DATABASE shop CHANGEFEED 3d COMMENT 'Orders and customers'"
+/// );
+/// # Ok::<_, surrealdb_abstraction::db::QueryBuildError>(())
+/// ```
+#[derive(Clone, Debug)]
+pub struct DefineDatabase {
+    name: Ident,
+    mode: DefineMode,
+    changefeed: Option<Duration>,
+    comment: Option<String>,
+}
+
+impl DefineDatabase {
+    pub fn new(name: impl Into<Ident>) -> Self {
+        Self {
+            name: name.into(),
+            mode: DefineMode::default(),
+            changefeed: None,
+            comment: None,
+        }
+    }
+
+    /// Keeps a change feed of every table in the database for `duration`.
+    pub fn changefeed(mut self, duration: impl Into<Duration>) -> Self {
+        self.changefeed = Some(duration.into());
+
+        self
+    }
+}
+
+impl_define!(DefineDatabase);
+
+impl Query for DefineDatabase {
+    fn construct_with(self, bindings: &mut Bindings) -> Result<String, QueryBuildError> {
+        bindings.mark_inlined();
+
+        self.name.validate()?;
+
+        let mut query = format!("DEFINE DATABASE{} {}", self.mode.clause(), self.name);
+
+        if let Some(duration) = self.changefeed {
+            query.push_str(&format!(" CHANGEFEED {}", duration));
+        }
+
+        query.push_str(&comment(self.comment));
+
+        Ok(query)
+    }
+}
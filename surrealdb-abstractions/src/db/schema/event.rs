@@ -0,0 +1,114 @@
+use crate::db::schema::{comment, condition, impl_define, impl_execute, statement, DefineMode};
+use crate::db::{Bindings, Condition, Ident, Query, QueryBuildError};
+
+/// `DEFINE EVENT`, runs statements whenever a record of the table changes.
+///
+/// The statements can use `$event` (`"CREATE"`, `"UPDATE"` or `"DELETE"`), `$before` and
+/// `$after`. Builder values are written into the definition, so pass a string for statements
+/// that need those parameters.
+///
+/// # Examples
+///
+/// ```
+/// # use surrealdb_abstraction::db::{Condition, DefineEvent, FieldPath, Query};
+///
+/// let query = DefineEvent::new("email_changed", "user")
+///     .when(Condition::eq(FieldPath::raw("$event"), "UPDATE"))
+///     .then("CREATE log SET user = $value.id, email = $after.email")
+///     .try_construct()?;
+///
+/// assert_eq!(
+///     query.query,
+///     "DEFINE EVENT email_changed ON TABLE user WHEN $event = 'UPDATE' THEN { CREATE log SET user = $value.id, email = $after.email }"
+/// );
+/// # Ok::<_, surrealdb_abstraction::db::QueryBuildError>(())
+/// ```
+#[derive(Clone, Debug)]
+pub struct DefineEvent {
+    name: Ident,
+    table: Ident,
+    mode: DefineMode,
+    when: Option<Condition>,
+    then: Vec<String>,
+    error: Option<QueryBuildError>,
+    comment: Option<String>,
+}
+
+impl DefineEvent {
+    pub fn new(name: impl Into<Ident>, table: impl Into<Ident>) -> Self {
+        Self {
+            name: name.into(),
+            table: table.into(),
+            mode: DefineMode::default(),
+            when: None,
+            then: Vec::new(),
+            error: None,
+            comment: None,
+        }
+    }
+
+    /// Only runs the event when `condition` matches.
+    pub fn when(mut self, condition: Condition) -> Self {
+        self.when = Some(condition);
+
+        self
+    }
+
+    /// Adds a statement to run, statements run in the order they were added.
+    ///
+    /// If the statement cannot be constructed, the definition fails with that error when it is
+    /// constructed or run.
+    pub fn then(mut self, query: impl Query) -> Self {
+        match statement(query) {
+            Ok(statement) => self.then.push(statement),
+            Err(err) => {
+                self.error.get_or_insert(err);
+            }
+        }
+
+        self
+    }
+}
+
+impl_define!(DefineEvent);
+
+impl Query for DefineEvent {
+    fn construct_with(self, bindings: &mut Bindings) -> Result<String, QueryBuildError> {
+        bindings.mark_inlined();
+
+        if self.table.is_empty() {
+            return Err(QueryBuildError::EmptyTableName);
+        }
+
+        if let Some(err) = self.error {
+            return Err(err);
+        }
+
+        self.name.validate()?;
+        self.table.validate()?;
+
+        if self.then.is_empty() {
+            return Err(QueryBuildError::MissingClause {
+                statement: "DEFINE EVENT",
+                clause: "THEN",
+            });
+        }
+
+        let mut query = format!(
+            "DEFINE EVENT{} {} ON TABLE {}",
+            self.mode.clause(),
+            self.name,
+            self.table
+        );
+
+        if let Some(when) = self.when {
+            query.push_str(&format!(" WHEN {}", condition(when)?));
+        }
+
+        query.push_str(&format!(" THEN {{ {} }}", self.then.join("; ")));
+
+        query.push_str(&comment(self.comment));
+
+        Ok(query)
+    }
+}
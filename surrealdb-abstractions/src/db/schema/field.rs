@@ -0,0 +1,161 @@
+use crate::db::schema::{
+    comment, condition, impl_define, impl_execute, literal, DefineMode, Permissions,
+};
+use crate::db::{Bindings, Condition, FieldPath, Ident, Query, QueryBuildError};
+use surrealdb::sql::{Kind, Value};
+
+/// `DEFINE FIELD`
+///
+/// Conditions passed to [`assert`](Self::assert) refer to the new value as `$value`, use
+/// [`FieldPath::raw`] to compare against it.
+///
+/// # Examples
+///
+/// ```
+/// # use surrealdb::sql::Kind;
+/// # use surrealdb_abstraction::db::{Condition, DefineField, FieldPath, Query};
+///
+/// let query = DefineField::new("age", "user")
+///     .kind(Kind::Int)
+///     .default(18)
+///     .assert(Condition::compare(FieldPath::raw("$value"), ">=", 0))
+///     .try_construct()?;
+/// assert_eq!(
+///     query.query,
+///     "DEFINE FIELD age ON TABLE user TYPE int DEFAULT 18 ASSERT $value >= 0"
+/// );
+///
+/// let query = DefineField::new("created", "user")
+///     .kind(Kind::Option(Box::new(Kind::Datetime)))
+///     .readonly()
+///     .try_construct()?;
+/// assert_eq!(
+///     query.query,
+///     "DEFINE FIELD created ON TABLE user TYPE option<datetime> READONLY"
+/// );
+/// # Ok::<_, surrealdb_abstraction::db::QueryBuildError>(())
+/// ```
+#[derive(Clone, Debug)]
+pub struct DefineField {
+    name: FieldPath,
+    table: Ident,
+    mode: DefineMode,
+    flexible: bool,
+    kind: Option<Kind>,
+    default: Option<Value>,
+    readonly: bool,
+    assert: Option<Condition>,
+    permissions: Option<Permissions>,
+    comment: Option<String>,
+}
+
+impl DefineField {
+    pub fn new(name: impl Into<FieldPath>, table: impl Into<Ident>) -> Self {
+        Self {
+            name: name.into(),
+            table: table.into(),
+            mode: DefineMode::default(),
+            flexible: false,
+            kind: None,
+            default: None,
+            readonly: false,
+            assert: None,
+            permissions: None,
+            comment: None,
+        }
+    }
+
+    /// `TYPE`, e.g. `Kind::Option(Box::new(Kind::String))` for `option<string>`.
+    pub fn kind(mut self, kind: Kind) -> Self {
+        self.kind = Some(kind);
+
+        self
+    }
+
+    /// `FLEXIBLE`, lets an `object` field on a schemafull table hold any fields.
+    pub fn flexible(mut self) -> Self {
+        self.flexible = true;
+
+        self
+    }
+
+    /// The value the field gets when a record is created without it.
+    pub fn default<T>(mut self, value: T) -> Self
+    where
+        Value: From<T>,
+    {
+        self.default = Some(Value::from(value));
+
+        self
+    }
+
+    /// `READONLY`, the field cannot be changed once the record is created.
+    pub fn readonly(mut self) -> Self {
+        self.readonly = true;
+
+        self
+    }
+
+    /// Rejects values for which `condition` does not match.
+    pub fn assert(mut self, condition: Condition) -> Self {
+        self.assert = Some(condition);
+
+        self
+    }
+
+    pub fn permissions(mut self, permissions: Permissions) -> Self {
+        self.permissions = Some(permissions);
+
+        self
+    }
+}
+
+impl_define!(DefineField);
+
+impl Query for DefineField {
+    fn construct_with(self, bindings: &mut Bindings) -> Result<String, QueryBuildError> {
+        bindings.mark_inlined();
+
+        if self.table.is_empty() {
+            return Err(QueryBuildError::EmptyTableName);
+        }
+
+        self.name.validate()?;
+        self.table.validate()?;
+
+        let mut query = format!(
+            "DEFINE FIELD{} {} ON TABLE {}",
+            self.mode.clause(),
+            self.name,
+            self.table
+        );
+
+        if self.flexible {
+            query.push_str(" FLEXIBLE");
+        }
+
+        if let Some(kind) = self.kind {
+            query.push_str(&format!(" TYPE {}", kind));
+        }
+
+        if let Some(value) = self.default {
+            query.push_str(&format!(" DEFAULT {}", literal(value)));
+        }
+
+        if self.readonly {
+            query.push_str(" READONLY");
+        }
+
+        if let Some(assert) = self.assert {
+            query.push_str(&format!(" ASSERT {}", condition(assert)?));
+        }
+
+        if let Some(permissions) = self.permissions {
+            query.push_str(&format!(" {}", permissions.construct()?));
+        }
+
+        query.push_str(&comment(self.comment));
+
+        Ok(query)
+    }
+}
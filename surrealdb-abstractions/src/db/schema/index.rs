@@ -0,0 +1,224 @@
+use crate::db::schema::{comment, impl_define, impl_execute, DefineMode};
+use crate::db::{Bindings, FieldPath, Ident, Query, QueryBuildError};
+use surrealdb::sql::index::Distance;
+
+/// What an index does, besides speeding up lookups by its fields.
+#[derive(Clone, Debug, PartialEq)]
+pub enum IndexKind {
+    /// `UNIQUE`, rejects records with the same values as another record.
+    Unique,
+    /// `SEARCH ANALYZER`, a full-text index using an analyzer defined with `DEFINE ANALYZER`.
+    Search {
+        analyzer: Ident,
+        /// The `k1` and `b` parameters of BM25 ranking, SurrealDB's defaults if `None`.
+        bm25: Option<(f32, f32)>,
+        /// Allows `search::highlight` on matches.
+        highlights: bool,
+    },
+    /// `MTREE`, a vector index for exact nearest neighbour searches.
+    MTree { dimension: u16, distance: Distance },
+    /// `HNSW`, a vector index for approximate nearest neighbour searches.
+    Hnsw {
+        dimension: u16,
+        distance: Distance,
+        /// The size of the candidate list while building the index (`EFC`).
+        ef_construction: Option<u16>,
+        /// The maximum number of connections per node (`M`).
+        m: Option<u8>,
+    },
+}
+
+impl IndexKind {
+    /// A full-text index using `analyzer` with the default BM25 ranking.
+    pub fn search(analyzer: impl Into<Ident>) -> Self {
+        IndexKind::Search {
+            analyzer: analyzer.into(),
+            bm25: None,
+            highlights: false,
+        }
+    }
+
+    fn construct(self) -> Result<String, QueryBuildError> {
+        match self {
+            IndexKind::Unique => Ok("UNIQUE".to_string()),
+            IndexKind::Search {
+                analyzer,
+                bm25,
+                highlights,
+            } => {
+                analyzer.validate()?;
+
+                let mut clause = format!("SEARCH ANALYZER {}", analyzer);
+
+                match bm25 {
+                    Some((k1, b)) => clause.push_str(&format!(" BM25({}, {})", k1, b)),
+                    None => clause.push_str(" BM25"),
+                }
+
+                if highlights {
+                    clause.push_str(" HIGHLIGHTS");
+                }
+
+                Ok(clause)
+            }
+            IndexKind::MTree {
+                dimension,
+                distance,
+            } => Ok(format!("MTREE DIMENSION {} DIST {}", dimension, distance)),
+            IndexKind::Hnsw {
+                dimension,
+                distance,
+                ef_construction,
+                m,
+            } => {
+                let mut clause = format!("HNSW DIMENSION {} DIST {}", dimension, distance);
+
+                if let Some(ef_construction) = ef_construction {
+                    clause.push_str(&format!(" EFC {}", ef_construction));
+                }
+
+                if let Some(m) = m {
+                    clause.push_str(&format!(" M {}", m));
+                }
+
+                Ok(clause)
+            }
+        }
+    }
+}
+
+/// `DEFINE INDEX`
+///
+/// # Examples
+///
+/// ```
+/// # use surrealdb::sql::index::Distance;
+/// # use surrealdb_abstraction::db::{DefineIndex, IndexKind, Query};
+///
+/// let query = DefineIndex::new("user_email", "user")
+///     .fields(["email"])
+///     .unique()
+///     .try_construct()?;
+/// assert_eq!(query.query, "DEFINE INDEX user_email ON TABLE user FIELDS email UNIQUE");
+///
+/// let query = DefineIndex::new("post_body", "post")
+///     .fields(["body"])
+///     .kind(IndexKind::search("english"))
+///     .try_construct()?;
+/// assert_eq!(
+///     query.query,
+///     "DEFINE INDEX post_body ON TABLE post FIELDS body SEARCH ANALYZER english BM25"
+/// );
+///
+/// let query = DefineIndex::new("post_embedding", "post")
+///     .fields(["embedding"])
+///     .hnsw(384, Distance::Cosine)
+///     .try_construct()?;
+/// assert_eq!(
+///     query.query,
+///     "DEFINE INDEX post_embedding ON TABLE post FIELDS embedding HNSW DIMENSION 384 DIST COSINE"
+/// );
+/// # Ok::<_, surrealdb_abstraction::db::QueryBuildError>(())
+/// ```
+#[derive(Clone, Debug)]
+pub struct DefineIndex {
+    name: Ident,
+    table: Ident,
+    mode: DefineMode,
+    fields: Vec<FieldPath>,
+    kind: Option<IndexKind>,
+    comment: Option<String>,
+}
+
+impl DefineIndex {
+    pub fn new(name: impl Into<Ident>, table: impl Into<Ident>) -> Self {
+        Self {
+            name: name.into(),
+            table: table.into(),
+            mode: DefineMode::default(),
+            fields: Vec::new(),
+            kind: None,
+            comment: None,
+        }
+    }
+
+    /// The indexed fields, in order.
+    pub fn fields<F>(mut self, fields: impl IntoIterator<Item = F>) -> Self
+    where
+        F: Into<FieldPath>,
+    {
+        self.fields.extend(fields.into_iter().map(Into::into));
+
+        self
+    }
+
+    pub fn kind(mut self, kind: IndexKind) -> Self {
+        self.kind = Some(kind);
+
+        self
+    }
+
+    pub fn unique(self) -> Self {
+        self.kind(IndexKind::Unique)
+    }
+
+    pub fn mtree(self, dimension: u16, distance: Distance) -> Self {
+        self.kind(IndexKind::MTree {
+            dimension,
+            distance,
+        })
+    }
+
+    pub fn hnsw(self, dimension: u16, distance: Distance) -> Self {
+        self.kind(IndexKind::Hnsw {
+            dimension,
+            distance,
+            ef_construction: None,
+            m: None,
+        })
+    }
+}
+
+impl_define!(DefineIndex);
+
+impl Query for DefineIndex {
+    fn construct_with(self, bindings: &mut Bindings) -> Result<String, QueryBuildError> {
+        bindings.mark_inlined();
+
+        if self.table.is_empty() {
+            return Err(QueryBuildError::EmptyTableName);
+        }
+
+        self.name.validate()?;
+        self.table.validate()?;
+
+        if self.fields.is_empty() {
+            return Err(QueryBuildError::MissingClause {
+                statement: "DEFINE INDEX",
+                clause: "FIELDS",
+            });
+        }
+
+        self.fields.iter().try_for_each(FieldPath::validate)?;
+
+        let mut query = format!(
+            "DEFINE INDEX{} {} ON TABLE {} FIELDS {}",
+            self.mode.clause(),
+            self.name,
+            self.table,
+            self.fields
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+
+        if let Some(kind) = self.kind {
+            query.push_str(&format!(" {}", kind.construct()?));
+        }
+
+        query.push_str(&comment(self.comment));
+
+        Ok(query)
+    }
+}
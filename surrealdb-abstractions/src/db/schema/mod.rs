@@ -0,0 +1,131 @@
+//! Builders for the `DEFINE` and `REMOVE` statements that manage the schema.
+//!
+//! Definitions implement [`Query`], so they can be executed on their own or
+//! added to a [`Batch`](crate::db::Batch) or [`Transaction`](crate::db::Transaction) like any
+//! other statement. SurrealDB stores the clauses of a definition and evaluates them later, when
+//! query parameters are no longer available, so values are written into the statement instead
+//! of being bound. For that reason the text of a definition is never reported to a
+//! [`QueryObserver`](crate::db::QueryObserver) or recorded in a span.
+
+pub use database::*;
+pub use event::*;
+pub use field::*;
+pub use index::*;
+pub use namespace::*;
+pub use permissions::*;
+pub use remove::*;
+pub use table::*;
+
+pub mod database;
+pub mod event;
+pub mod field;
+pub mod index;
+pub mod namespace;
+pub mod permissions;
+pub mod remove;
+pub mod table;
+
+use crate::db::{Bindings, BoundQuery, Condition, Query, QueryBuildError};
+use surrealdb::sql::Value;
+
+/// What a `DEFINE` statement does when the definition already exists.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum DefineMode {
+    /// Fails with an error.
+    #[default]
+    Create,
+    /// `OVERWRITE`, replaces the existing definition.
+    Overwrite,
+    /// `IF NOT EXISTS`, keeps the existing definition.
+    IfNotExists,
+}
+
+impl DefineMode {
+    fn clause(&self) -> &'static str {
+        match self {
+            DefineMode::Create => "",
+            DefineMode::Overwrite => " OVERWRITE",
+            DefineMode::IfNotExists => " IF NOT EXISTS",
+        }
+    }
+}
+
+/// Adds `overwrite`, `if_not_exists` and `comment` to a definition builder with `mode` and
+/// `comment` fields, and `execute` to send it.
+macro_rules! impl_define {
+    ($builder:ident) => {
+        impl $builder {
+            /// Replaces the definition if it already exists.
+            pub fn overwrite(mut self) -> Self {
+                self.mode = $crate::db::DefineMode::Overwrite;
+
+                self
+            }
+
+            /// Keeps the existing definition, instead of failing, if it already exists.
+            pub fn if_not_exists(mut self) -> Self {
+                self.mode = $crate::db::DefineMode::IfNotExists;
+
+                self
+            }
+
+            pub fn comment(mut self, comment: impl Into<String>) -> Self {
+                self.comment = Some(comment.into());
+
+                self
+            }
+        }
+
+        impl_execute!($builder);
+    };
+}
+
+/// Adds `execute` to a schema statement builder.
+macro_rules! impl_execute {
+    ($builder:ident) => {
+        impl $builder {
+            pub async fn execute<E>(self, db: &E) -> $crate::Result<()>
+            where
+                E: $crate::db::Executor + ?Sized,
+            {
+                $crate::db::runner::Runner::execute(db, self, None)
+                    .await
+                    .map_err(Into::into)
+            }
+        }
+    };
+}
+
+pub(crate) use impl_define;
+pub(crate) use impl_execute;
+
+/// Renders `value` as a SurrealQL literal.
+fn literal(value: impl Into<Value>) -> String {
+    value.into().to_string()
+}
+
+/// Renders ` COMMENT "..."` if there is a comment.
+fn comment(comment: Option<String>) -> String {
+    comment
+        .map(|comment| format!(" COMMENT {}", literal(comment)))
+        .unwrap_or_default()
+}
+
+/// Renders `condition` with its values written in place.
+fn condition(condition: Condition) -> Result<String, QueryBuildError> {
+    let mut bindings = Bindings::default();
+    let condition = condition.construct_with(&mut bindings)?;
+
+    Ok(BoundQuery::new(condition, bindings).inline())
+}
+
+/// Renders `query` with its values written in place and without a trailing `;`.
+fn statement(query: impl Query) -> Result<String, QueryBuildError> {
+    Ok(query
+        .try_construct()?
+        .inline()
+        .trim()
+        .trim_end_matches(';')
+        .trim()
+        .to_string())
+}
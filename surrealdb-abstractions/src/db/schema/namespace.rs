@@ -0,0 +1,50 @@
+use crate::db::schema::{comment, impl_define, impl_execute, DefineMode};
+use crate::db::{Bindings, Ident, Query, QueryBuildError};
+
+/// `DEFINE NAMESPACE`
+///
+/// # Examples
+///
+/// ```
+/// # use surrealdb_abstraction::db::{DefineNamespace, Query};
+///
+/// let query = DefineNamespace::new("app").if_not_exists().try_construct()?;
+///
+/// assert_eq!(query.query, "DEFINE NAMESPACE IF NOT EXISTS app");
+/// # Ok::<_, surrealdb_abstraction::db::QueryBuildError>(())
+/// ```
+#[derive(Clone, Debug)]
+pub struct DefineNamespace {
+    name: Ident,
+    mode: DefineMode,
+    comment: Option<String>,
+}
+
+impl DefineNamespace {
+    pub fn new(name: impl Into<Ident>) -> Self {
+        Self {
+            name: name.into(),
+            mode: DefineMode::default(),
+            comment: None,
+        }
+    }
+}
+
+impl_define!(DefineNamespace);
+
+impl Query for DefineNamespace {
+    fn construct_with(self, bindings: &mut Bindings) -> Result<String, QueryBuildError> {
+        bindings.mark_inlined();
+
+        self.name.validate()?;
+
+        let query = format!(
+            "DEFINE NAMESPACE{} {}{}",
+            self.mode.clause(),
+            self.name,
+            comment(self.comment)
+        );
+
+        Ok(query)
+    }
+}
@@ -0,0 +1,142 @@
+use crate::db::{Condition, QueryBuildError};
+use std::fmt::{Display, Formatter};
+
+/// An action [`Permissions`] can be granted for.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Action {
+    Select,
+    Create,
+    Update,
+    /// Only applies to tables.
+    Delete,
+}
+
+impl Display for Action {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Action::Select => f.write_str("select"),
+            Action::Create => f.write_str("create"),
+            Action::Update => f.write_str("update"),
+            Action::Delete => f.write_str("delete"),
+        }
+    }
+}
+
+/// Who may perform an [`Action`].
+#[derive(Clone, Debug)]
+pub enum Permission {
+    /// Nobody but system users.
+    None,
+    /// Everybody.
+    Full,
+    /// Only when the condition matches, e.g. `user = $auth.id`.
+    Where(Condition),
+}
+
+impl From<Condition> for Permission {
+    fn from(condition: Condition) -> Self {
+        Permission::Where(condition)
+    }
+}
+
+/// The `PERMISSIONS` clause of a table or field.
+///
+/// Actions without a permission get SurrealDB's default for the definition: `NONE` for tables
+/// and `FULL` for fields.
+///
+/// # Examples
+///
+/// ```
+/// # use surrealdb_abstraction::db::{Action, Condition, DefineTable, FieldPath, Permission, Permissions, Query};
+///
+/// let permissions = Permissions::new()
+///     .grant([Action::Select], Permission::Full)
+///     .grant(
+///         [Action::Update, Action::Delete],
+///         Condition::is_not_none(FieldPath::raw("$auth.id")),
+///     );
+///
+/// let query = DefineTable::new("post").permissions(permissions).try_construct()?;
+///
+/// assert_eq!(
+///     query.query,
+///     "DEFINE TABLE post PERMISSIONS FOR select FULL, FOR update, delete WHERE $auth.id IS NOT NONE"
+/// );
+/// # Ok::<_, surrealdb_abstraction::db::QueryBuildError>(())
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct Permissions {
+    all: Option<bool>,
+    grants: Vec<(Vec<Action>, Permission)>,
+}
+
+impl Permissions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// `PERMISSIONS NONE`
+    pub fn none() -> Self {
+        Self {
+            all: Some(false),
+            grants: Vec::new(),
+        }
+    }
+
+    /// `PERMISSIONS FULL`
+    pub fn full() -> Self {
+        Self {
+            all: Some(true),
+            grants: Vec::new(),
+        }
+    }
+
+    /// Grants `actions` to whoever `permission` allows, e.g. `FOR update, delete WHERE ...`.
+    pub fn grant(
+        mut self,
+        actions: impl IntoIterator<Item = Action>,
+        permission: impl Into<Permission>,
+    ) -> Self {
+        self.all = None;
+        self.grants
+            .push((actions.into_iter().collect(), permission.into()));
+
+        self
+    }
+
+    pub(crate) fn construct(self) -> Result<String, QueryBuildError> {
+        match self.all {
+            Some(true) => return Ok("PERMISSIONS FULL".to_string()),
+            Some(false) => return Ok("PERMISSIONS NONE".to_string()),
+            None => {}
+        }
+
+        if self.grants.is_empty() {
+            return Ok("PERMISSIONS NONE".to_string());
+        }
+
+        let grants = self
+            .grants
+            .into_iter()
+            .map(|(actions, permission)| {
+                let actions = actions
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>()
+                    .join(", ");
+
+                let permission = match permission {
+                    Permission::None => "NONE".to_string(),
+                    Permission::Full => "FULL".to_string(),
+                    Permission::Where(condition) => {
+                        format!("WHERE {}", super::condition(condition)?)
+                    }
+                };
+
+                Ok(format!("FOR {} {}", actions, permission))
+            })
+            .collect::<Result<Vec<_>, QueryBuildError>>()?;
+
+        Ok(format!("PERMISSIONS {}", grants.join(", ")))
+    }
+}
@@ -0,0 +1,115 @@
+use crate::db::schema::impl_execute;
+use crate::db::{Bindings, FieldPath, Ident, Query, QueryBuildError};
+
+#[derive(Clone, Debug)]
+enum Definition {
+    Namespace(Ident),
+    Database(Ident),
+    Table(Ident),
+    Field(FieldPath, Ident),
+    Index(Ident, Ident),
+    Event(Ident, Ident),
+}
+
+/// `REMOVE NAMESPACE`, `REMOVE DATABASE`, `REMOVE TABLE`, `REMOVE FIELD`, `REMOVE INDEX` or
+/// `REMOVE EVENT`, the counterparts of the `DEFINE` builders.
+///
+/// # Examples
+///
+/// ```
+/// # use surrealdb_abstraction::db::{Query, Remove};
+///
+/// let query = Remove::field("age", "user").if_exists().try_construct()?;
+///
+/// assert_eq!(query.query, "REMOVE FIELD IF EXISTS age ON TABLE user");
+/// # Ok::<_, surrealdb_abstraction::db::QueryBuildError>(())
+/// ```
+#[derive(Clone, Debug)]
+pub struct Remove {
+    definition: Definition,
+    if_exists: bool,
+}
+
+impl Remove {
+    fn new(definition: Definition) -> Self {
+        Self {
+            definition,
+            if_exists: false,
+        }
+    }
+
+    pub fn namespace(name: impl Into<Ident>) -> Self {
+        Self::new(Definition::Namespace(name.into()))
+    }
+
+    pub fn database(name: impl Into<Ident>) -> Self {
+        Self::new(Definition::Database(name.into()))
+    }
+
+    pub fn table(name: impl Into<Ident>) -> Self {
+        Self::new(Definition::Table(name.into()))
+    }
+
+    pub fn field(name: impl Into<FieldPath>, table: impl Into<Ident>) -> Self {
+        Self::new(Definition::Field(name.into(), table.into()))
+    }
+
+    pub fn index(name: impl Into<Ident>, table: impl Into<Ident>) -> Self {
+        Self::new(Definition::Index(name.into(), table.into()))
+    }
+
+    pub fn event(name: impl Into<Ident>, table: impl Into<Ident>) -> Self {
+        Self::new(Definition::Event(name.into(), table.into()))
+    }
+
+    /// Succeeds, instead of failing, if the definition does not exist.
+    pub fn if_exists(mut self) -> Self {
+        self.if_exists = true;
+
+        self
+    }
+}
+
+impl_execute!(Remove);
+
+impl Query for Remove {
+    fn construct_with(self, _: &mut Bindings) -> Result<String, QueryBuildError> {
+        let ident = |name: Ident| name.validate().map(|_| name.to_string());
+
+        let (kind, name, table) = match self.definition {
+            Definition::Namespace(name) => ("NAMESPACE", ident(name)?, None),
+            Definition::Database(name) => ("DATABASE", ident(name)?, None),
+            Definition::Table(name) => {
+                if name.is_empty() {
+                    return Err(QueryBuildError::EmptyTableName);
+                }
+
+                ("TABLE", ident(name)?, None)
+            }
+            Definition::Field(name, table) => {
+                name.validate()?;
+                ("FIELD", name.to_string(), Some(table))
+            }
+            Definition::Index(name, table) => ("INDEX", ident(name)?, Some(table)),
+            Definition::Event(name, table) => ("EVENT", ident(name)?, Some(table)),
+        };
+
+        let mut query = format!(
+            "REMOVE {}{} {}",
+            kind,
+            if self.if_exists { " IF EXISTS" } else { "" },
+            name
+        );
+
+        if let Some(table) = table {
+            if table.is_empty() {
+                return Err(QueryBuildError::EmptyTableName);
+            }
+
+            table.validate()?;
+            query.push_str(&format!(" ON TABLE {}", table));
+        }
+
+        Ok(query)
+    }
+}
@@ -0,0 +1,227 @@
+use crate::db::schema::{comment, impl_define, impl_execute, DefineMode, Permissions};
+use crate::db::{Bindings, Ident, Query, QueryBuildError, QueryBuilder, Select};
+use surrealdb::sql::Duration;
+
+/// Whether a table only accepts the fields defined on it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Schema {
+    /// `SCHEMAFULL`, fields without a definition are dropped.
+    Full,
+    /// `SCHEMALESS`, any field is accepted.
+    Less,
+}
+
+/// The kind of records a table holds.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum TableType {
+    /// `TYPE ANY`, normal records and relations.
+    Any,
+    /// `TYPE NORMAL`, no relations.
+    Normal,
+    /// `TYPE RELATION`, only relations, optionally restricted to the tables at either end.
+    Relation {
+        from: Vec<Ident>,
+        to: Vec<Ident>,
+        /// Requires the records at both ends to exist.
+        enforced: bool,
+    },
+}
+
+impl TableType {
+    fn construct(self) -> Result<String, QueryBuildError> {
+        let tables = |tables: Vec<Ident>| {
+            tables.iter().try_for_each(Ident::validate)?;
+
+            Ok::<_, QueryBuildError>(
+                tables
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>()
+                    .join(" | "),
+            )
+        };
+
+        match self {
+            TableType::Any => Ok("TYPE ANY".to_string()),
+            TableType::Normal => Ok("TYPE NORMAL".to_string()),
+            TableType::Relation { from, to, enforced } => {
+                let mut clause = "TYPE RELATION".to_string();
+
+                if !from.is_empty() {
+                    clause.push_str(&format!(" FROM {}", tables(from)?));
+                }
+
+                if !to.is_empty() {
+                    clause.push_str(&format!(" TO {}", tables(to)?));
+                }
+
+                if enforced {
+                    clause.push_str(" ENFORCED");
+                }
+
+                Ok(clause)
+            }
+        }
+    }
+}
+
+/// `DEFINE TABLE`
+///
+/// # Examples
+///
+/// ```
+/// # use std::time::Duration;
+/// # use surrealdb_abstraction::db::{DefineTable, Permissions, Query};
+///
+/// let query = DefineTable::new("user")
+///     .schemafull()
+///     .changefeed(Duration::from_secs(60 * 60))
+///     .permissions(Permissions::full())
+///     .try_construct()?;
+/// assert_eq!(
+///     query.query,
+///     "DEFINE TABLE user SCHEMAFULL CHANGEFEED 1h PERMISSIONS FULL"
+/// );
+///
+/// let query = DefineTable::new("likes")
+///     .relation(["user"], ["post"])
+///     .try_construct()?;
+/// assert_eq!(query.query, "DEFINE TABLE likes TYPE RELATION FROM user TO post");
+/// # Ok::<_, surrealdb_abstraction::db::QueryBuildError>(())
+/// ```
+#[derive(Clone, Debug)]
+pub struct DefineTable {
+    name: Ident,
+    mode: DefineMode,
+    drop: bool,
+    schema: Option<Schema>,
+    table_type: Option<TableType>,
+    view: Option<QueryBuilder<Select>>,
+    changefeed: Option<Duration>,
+    permissions: Option<Permissions>,
+    comment: Option<String>,
+}
+
+impl DefineTable {
+    pub fn new(name: impl Into<Ident>) -> Self {
+        Self {
+            name: name.into(),
+            mode: DefineMode::default(),
+            drop: false,
+            schema: None,
+            table_type: None,
+            view: None,
+            changefeed: None,
+            permissions: None,
+            comment: None,
+        }
+    }
+
+    pub fn schemafull(mut self) -> Self {
+        self.schema = Some(Schema::Full);
+
+        self
+    }
+
+    pub fn schemaless(mut self) -> Self {
+        self.schema = Some(Schema::Less);
+
+        self
+    }
+
+    /// `DROP`, records written to the table are not stored.
+    pub fn drop(mut self) -> Self {
+        self.drop = true;
+
+        self
+    }
+
+    pub fn table_type(mut self, table_type: TableType) -> Self {
+        self.table_type = Some(table_type);
+
+        self
+    }
+
+    /// `TYPE RELATION FROM ... TO ...`, leave `from` or `to` empty to allow any table.
+    pub fn relation<F, T>(
+        self,
+        from: impl IntoIterator<Item = F>,
+        to: impl IntoIterator<Item = T>,
+    ) -> Self
+    where
+        F: Into<Ident>,
+        T: Into<Ident>,
+    {
+        self.table_type(TableType::Relation {
+            from: from.into_iter().map(Into::into).collect(),
+            to: to.into_iter().map(Into::into).collect(),
+            enforced: false,
+        })
+    }
+
+    /// `AS SELECT ...`, a table view kept up to date with the results of `query`.
+    pub fn view(mut self, query: QueryBuilder<Select>) -> Self {
+        self.view = Some(query);
+
+        self
+    }
+
+    /// Keeps a change feed of the table for `duration`.
+    pub fn changefeed(mut self, duration: impl Into<Duration>) -> Self {
+        self.changefeed = Some(duration.into());
+
+        self
+    }
+
+    pub fn permissions(mut self, permissions: Permissions) -> Self {
+        self.permissions = Some(permissions);
+
+        self
+    }
+}
+
+impl_define!(DefineTable);
+
+impl Query for DefineTable {
+    fn construct_with(self, bindings: &mut Bindings) -> Result<String, QueryBuildError> {
+        bindings.mark_inlined();
+
+        if self.name.is_empty() {
+            return Err(QueryBuildError::EmptyTableName);
+        }
+
+        self.name.validate()?;
+
+        let mut query = format!("DEFINE TABLE{} {}", self.mode.clause(), self.name);
+
+        if self.drop {
+            query.push_str(" DROP");
+        }
+
+        match self.schema {
+            Some(Schema::Full) => query.push_str(" SCHEMAFULL"),
+            Some(Schema::Less) => query.push_str(" SCHEMALESS"),
+            None => {}
+        }
+
+        if let Some(table_type) = self.table_type {
+            query.push_str(&format!(" {}", table_type.construct()?));
+        }
+
+        if let Some(view) = self.view {
+            query.push_str(&format!(" AS {}", super::statement(view)?));
+        }
+
+        if let Some(duration) = self.changefeed {
+            query.push_str(&format!(" CHANGEFEED {}", duration));
+        }
+
+        if let Some(permissions) = self.permissions {
+            query.push_str(&format!(" {}", permissions.construct()?));
+        }
+
+        query.push_str(&comment(self.comment));
+
+        Ok(query)
+    }
+}
@@ -0,0 +1,227 @@
+use crate::db::error::DatabaseError;
+use crate::db::{
+    Condition, Create, Delete, Direction, FieldPath, OrderBy, QueryBuildError, QueryBuilder,
+    Select, Statement, Update,
+};
+use crate::Result;
+use std::collections::BTreeMap;
+use surrealdb::sql::{Id, Number, Thing, Value};
+
+/// A struct stored as the records of a table, usually implemented with
+/// `#[derive(SurrealTable)]`.
+///
+/// The derive reads the table name from `#[surreal(table = "...")]`, or uses the struct name in
+/// snake case. A field is stored under its `#[surreal(rename = "...")]` name, or the name serde
+/// serializes it with, including `#[serde(rename_all = "...")]` of the struct. It is left out
+/// with `#[surreal(skip)]`, `#[serde(skip)]` or `#[serde(skip_serializing)]`. The field named
+/// `id` is the record id.
+///
+/// Every stored field is converted from a clone of it, so it must implement `Clone`. Fields
+/// with `#[serde(flatten)]`, `#[serde(with = "...")]` or `#[serde(serialize_with = "...")]`
+/// would be stored differently than serde serializes them, so the derive rejects them.
+///
+/// The derive also adds a unit struct named after the struct with a `Fields` suffix, holding a
+/// [`Field`] constant for every stored field named after the field in upper case, like
+/// `UserFields::AGE`. They do not live on the struct itself, where they could clash with its
+/// own associated items.
+///
+/// # Examples
+///
+/// ```
+/// # use serde::{Deserialize, Serialize};
+/// # use surrealdb::sql::Thing;
+/// # use surrealdb_abstraction::db::{Query, SurrealTable};
+///
+/// #[derive(Clone, Serialize, Deserialize, SurrealTable)]
+/// #[surreal(table = "user")]
+/// struct User {
+///     id: Option<Thing>,
+///     name: String,
+///     #[serde(rename = "years")]
+///     age: u32,
+///     #[surreal(skip)]
+///     #[serde(skip)]
+///     session: Option<String>,
+/// }
+///
+/// let query = User::select()
+///     .and_where(UserFields::AGE.gt(18))
+///     .order_by([UserFields::NAME.asc()])
+///     .try_construct()?;
+///
+/// assert_eq!(query.query, "SELECT * FROM user WHERE years > $p0 ORDER BY name ASC");
+/// # Ok::<_, surrealdb_abstraction::db::QueryBuildError>(())
+/// ```
+///
+/// A flattened field is rejected:
+///
+/// ```compile_fail
+/// # use serde::{Deserialize, Serialize};
+/// # use surrealdb_abstraction::db::SurrealTable;
+/// #[derive(Clone, Serialize, Deserialize)]
+/// struct Address {
+///     city: String,
+/// }
+///
+/// #[derive(Clone, Serialize, Deserialize, SurrealTable)]
+/// struct User {
+///     name: String,
+///     #[serde(flatten)]
+///     address: Address,
+/// }
+/// ```
+pub trait SurrealTable {
+    const TABLE: &'static str;
+
+    /// The stored fields, without the record id.
+    fn content(&self) -> Result<BTreeMap<String, Value>>;
+
+    /// The record id, if the record has one.
+    fn record_id(&self) -> Result<Option<Value>>;
+
+    /// `SELECT * FROM table`
+    fn select() -> QueryBuilder<Select> {
+        Select::query(Self::TABLE)
+    }
+
+    /// Creates the record from its fields, with its id if it has one.
+    fn create_query(&self) -> Result<QueryBuilder<Create>> {
+        let query = Create::query(Self::TABLE).set_content(self.content()?);
+
+        match record_of(self)? {
+            Some(record) => Ok(query.set_from(record)),
+            None => Ok(query),
+        }
+    }
+
+    /// Merges the fields into the stored record with the same id.
+    fn update_query(&self) -> Result<QueryBuilder<Update>> {
+        Ok(Update::query(Self::TABLE)
+            .set_from(require_record(self)?)
+            .set_content(self.content()?))
+    }
+
+    /// Deletes the stored record with the same id.
+    fn delete_query(&self) -> Result<QueryBuilder<Delete>> {
+        Ok(Delete::query(Self::TABLE).set_from(require_record(self)?))
+    }
+}
+
+/// The record `record` is stored as, which it must have an id for.
+fn require_record<T>(record: &T) -> Result<Thing>
+where
+    T: SurrealTable + ?Sized,
+{
+    record_of(record)?.ok_or_else(|| {
+        DatabaseError::from(QueryBuildError::MissingRecordId(T::TABLE.to_string())).into()
+    })
+}
+
+/// The record `record` is stored as, if it has an id. An id that is not a record id, like a
+/// string or number, is the id of a record of the table.
+fn record_of<T>(record: &T) -> Result<Option<Thing>>
+where
+    T: SurrealTable + ?Sized,
+{
+    let invalid = |id: Value| -> crate::error::Error {
+        DatabaseError::from(QueryBuildError::InvalidRecordId {
+            table: T::TABLE.to_string(),
+            id: id.to_string(),
+        })
+        .into()
+    };
+
+    let id = match record.record_id()? {
+        Some(Value::Thing(thing)) if thing.tb == T::TABLE => return Ok(Some(thing)),
+        Some(Value::Strand(id)) => Id::String(id.0),
+        Some(Value::Number(Number::Int(id))) => Id::Number(id),
+        Some(Value::Uuid(id)) => Id::Uuid(id),
+        Some(Value::Array(id)) => Id::Array(id),
+        Some(Value::Object(id)) => Id::Object(id),
+        Some(id) => return Err(invalid(id)),
+        None => return Ok(None),
+    };
+
+    Ok(Some(Thing::from((T::TABLE, id))))
+}
+
+/// Converts a field of a [`SurrealTable`] into a value, used by the derive.
+#[doc(hidden)]
+pub fn to_field_value<T>(value: T) -> Result<Value>
+where
+    T: serde::Serialize + 'static,
+{
+    surrealdb::sql::to_value(value)
+        .map_err(|err| DatabaseError::SerializationError(err.to_string()).into())
+}
+
+/// The name of a stored field, for building conditions and sort keys without string literals.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Field(&'static str);
+
+macro_rules! impl_field_compare {
+    ( $( $name:ident ),* $(,)? ) => {
+        $(
+            #[doc = concat!("See [`Condition::", stringify!($name), "`].")]
+            pub fn $name<T>(self, value: T) -> Condition
+            where
+                Value: From<T>,
+            {
+                Condition::$name(self, value)
+            }
+        )*
+    };
+}
+
+impl Field {
+    pub const fn new(name: &'static str) -> Self {
+        Self(name)
+    }
+
+    pub const fn name(&self) -> &'static str {
+        self.0
+    }
+
+    impl_field_compare! {
+        eq,
+        not_eq,
+        gt,
+        gte,
+        lt,
+        lte,
+        is_in,
+        not_in,
+        contains,
+        contains_any,
+        contains_all,
+        inside,
+    }
+
+    pub fn is_none(self) -> Condition {
+        Condition::is_none(self)
+    }
+
+    pub fn is_not_none(self) -> Condition {
+        Condition::is_not_none(self)
+    }
+
+    pub fn asc(self) -> OrderBy {
+        OrderBy::new(self, Direction::Asc)
+    }
+
+    pub fn desc(self) -> OrderBy {
+        OrderBy::new(self, Direction::Desc)
+    }
+}
+
+impl From<Field> for FieldPath {
+    fn from(field: Field) -> Self {
+        FieldPath::from(field.0)
+    }
+}
+
+impl From<Field> for OrderBy {
+    fn from(field: Field) -> Self {
+        field.asc()
+    }
+}
@@ -1,14 +1,27 @@
 #![cfg(test)]
 
+use super::batch::*;
+use super::error::*;
+use super::executor::*;
 use super::filter::*;
+use super::metrics::*;
+use super::migration::*;
+use super::observer::*;
 use super::query::*;
+#[cfg(feature = "derive")]
+use super::repository::*;
+use super::retry::*;
+use super::schema::*;
+use super::transaction::*;
+#[cfg(feature = "derive")]
+use super::SurrealTable;
 use crate::db::create::Create;
 use crate::db::query::select::Select;
-use crate::test::TEST_DB;
-use crate::test::{db, init_db};
+use crate::test::db;
 use log::info;
+use std::collections::BTreeMap;
 use std::sync::Arc;
-use surrealdb::sql::Thing;
+use surrealdb::sql::{Object, Thing, Value};
 use tosic_logging_utils::init_test_logger;
 
 #[derive(Debug, serde::Deserialize, serde::Serialize, Clone)]
@@ -62,10 +75,12 @@ fn test() {
         .construct();
 
     let excepted =
-        "SELECT *, name AS username FROM user WHERE age > 18 AND username = 'admin' LIMIT 1"
+        "SELECT *, name AS username FROM user WHERE username = $p0 AND age > $p1 LIMIT 1"
             .to_string();
 
-    assert_eq!(query, excepted);
+    assert_eq!(query.query, excepted);
+    assert_eq!(query.bindings.get("p0"), Some(&Value::from("admin")));
+    assert_eq!(query.bindings.get("p1"), Some(&Value::from(18)));
 }
 
 #[tokio::test]
@@ -82,6 +97,23 @@ async fn test_run_query() -> anyhow::Result<()> {
 
     println!("Selected data: {:?}", res);
 
+    // Taken like the SDK does: a single record from an array, and an array from a single record.
+    let one: Option<TestData> = data.select_all_query().run(&db, 0).await?;
+    assert!(one.is_some());
+    let none: Option<TestData> = Select::query("test_data")
+        .add_condition("age", None, -1)
+        .run(&db, 0)
+        .await?;
+    assert!(none.is_none());
+    let only: Vec<TestData> = Select::query("test_data")
+        .set_only(true)
+        .set_from(res[0].id.clone().unwrap())
+        .run(&db, 0)
+        .await?;
+    assert_eq!(only.len(), 1);
+    let raw: surrealdb::Value = data.select_all_query().run(&db, 0).await?;
+    assert!(matches!(raw.into_inner(), Value::Array(rows) if rows.len() == 1));
+
     Ok(())
 }
 
@@ -102,7 +134,9 @@ async fn test_relate() -> anyhow::Result<()> {
         .set_parallel(true)
         .construct();
 
-    db.query(query).await?;
+    db.query(query.query)
+        .bind(Object::from(query.bindings))
+        .await?;
 
     let res: Vec<TestData> = data.select_all_query().run(&db, 0).await?;
 
@@ -122,8 +156,19 @@ async fn test_construct_create_query() -> anyhow::Result<()> {
 
     println!("{}", query);
     assert_eq!(
-        query,
-        "CREATE test_data CONTENT { age: 18,  name: 'John Doe',  type: 'friendship'}"
+        query.query,
+        "CREATE test_data CONTENT { age: $p0, name: $p1, type: $p2 }"
+    );
+    assert_eq!(query.bindings.len(), 3);
+
+    let query = Create::query("test_data")
+        .set_from(Thing::from(("test_data", "alice")))
+        .add_field_to_content("age", 30)
+        .try_construct()?;
+    assert_eq!(query.query, "CREATE $p0 CONTENT { age: $p1 }");
+    assert_eq!(
+        query.bindings.get("p0"),
+        Some(&Value::from(Thing::from(("test_data", "alice"))))
     );
 
     Ok(())
@@ -136,7 +181,2037 @@ async fn test_construct_select_query() -> anyhow::Result<()> {
     let query = data.select_all_query().construct();
 
     println!("{}", query);
-    assert_eq!(query, "SELECT * FROM test_data ".to_string());
+    assert_eq!(query.query, "SELECT * FROM test_data ".to_string());
+    assert!(query.bindings.is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn test_construct_insert_query() -> anyhow::Result<()> {
+    let query = Insert::query("test_data")
+        .set_ignore(true)
+        .add_record_from(&TestData::default())
+        .unwrap()
+        .on_duplicate(Assignment::increment("age", 1))
+        .set_return(Return::None)
+        .try_construct()?;
+
+    assert_eq!(
+        query.query,
+        "INSERT IGNORE INTO test_data $p0 ON DUPLICATE KEY UPDATE age += $p1 RETURN NONE"
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_run_bulk_insert() -> anyhow::Result<()> {
+    let db = Arc::new(db().await?);
+
+    let records = vec![
+        TestData::new("Alice".to_string(), 30),
+        TestData::new("Bob".to_string(), 40),
+    ];
+
+    let res: Vec<TestData> = Insert::query("test_data")
+        .add_records_from(&records)?
+        .run(&db, 0)
+        .await?;
+
+    assert_eq!(res.len(), 2);
+
+    let res: Vec<TestData> = TestData::default().select_all_query().run(&db, 0).await?;
+
+    assert_eq!(res.len(), 2);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_run_bound_filter() -> anyhow::Result<()> {
+    let db = Arc::new(db().await?);
+
+    let _: Vec<TestData> = TestData::new("Alice".to_string(), 30)
+        .create_query()
+        .run(&db, 0)
+        .await?;
+    let _: Vec<TestData> = TestData::new("Bob".to_string(), 40)
+        .create_query()
+        .run(&db, 0)
+        .await?;
+
+    let res: Vec<TestData> = Select::query("test_data")
+        .add_condition("name", None, "Alice")
+        .run(&db, 0)
+        .await?;
+
+    assert_eq!(res.len(), 1);
+    assert_eq!(res[0].name, "Alice");
+
+    Ok(())
+}
+
+#[test]
+fn test_construct_filter_tree() -> anyhow::Result<()> {
+    let filter = Filter::new(Condition::eq("status", "a").or(Condition::eq("status", "b")))
+        .and(Condition::eq("owner", "me"))
+        .and(!Condition::contains_any("tags", vec!["spam", "junk"]))
+        .and(Condition::is_none("deleted_at"))
+        .try_construct()?;
+
+    assert_eq!(
+        filter.query,
+        "WHERE (status = $p0 OR status = $p1) AND owner = $p2 AND !(tags CONTAINSANY $p3) AND deleted_at IS NONE"
+    );
+    assert_eq!(filter.bindings.len(), 4);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_run_filter_tree() -> anyhow::Result<()> {
+    let db = Arc::new(db().await?);
+
+    for (name, age) in [("Alice", 30), ("Bob", 40), ("Carol", 50)] {
+        let _: Vec<TestData> = TestData::new(name.to_string(), age)
+            .create_query()
+            .run(&db, 0)
+            .await?;
+    }
+
+    let res: Vec<TestData> = Select::query("test_data")
+        .and_where(Condition::eq("name", "Alice").or(Condition::gt("age", 45)))
+        .and_where(!Condition::is_in("name", vec!["Carol"]))
+        .run(&db, 0)
+        .await?;
+
+    assert_eq!(res.len(), 1);
+    assert_eq!(res[0].name, "Alice");
+
+    Ok(())
+}
+
+#[test]
+fn test_filter_keeps_repeated_conditions() -> anyhow::Result<()> {
+    let filter = Filter::default()
+        .add_condition("age".to_string(), Some(">".to_string()), 18)
+        .add_condition("age".to_string(), Some(">".to_string()), 21)
+        .add_condition("name".to_string(), None, "admin");
+
+    assert_eq!(
+        filter.clone().try_construct()?.query,
+        "WHERE age > $p0 AND age > $p1 AND name = $p2"
+    );
+
+    let replaced = filter
+        .clone()
+        .replace_condition("name".to_string(), None, "root")
+        .try_construct()?;
+
+    assert_eq!(
+        replaced.query,
+        "WHERE age > $p0 AND age > $p1 AND name = $p2"
+    );
+    assert_eq!(replaced.bindings.get("p2"), Some(&Value::from("root")));
+
+    // Without a matching comparison, the condition is added.
+    let added = filter
+        .clone()
+        .replace_condition("role".to_string(), None, "admin")
+        .try_construct()?;
+
+    assert_eq!(
+        added.query,
+        "WHERE age > $p0 AND age > $p1 AND name = $p2 AND role = $p3"
+    );
+    assert_eq!(added.bindings.get("p3"), Some(&Value::from("admin")));
+
+    // Comparisons inside `OR` and `NOT` are not top-level conjuncts, so they are kept as is.
+    let nested = Filter::new(!Condition::eq("name", "admin"))
+        .or(Condition::eq("name", "root"))
+        .replace_condition("name".to_string(), None, "guest")
+        .try_construct()?;
+
+    assert_eq!(
+        nested.query,
+        "WHERE (!(name = $p0) OR name = $p1) AND name = $p2"
+    );
+    assert_eq!(nested.bindings.get("p0"), Some(&Value::from("admin")));
+
+    let removed = filter
+        .remove_condition("age".to_string(), Some(">".to_string()))
+        .try_construct()?;
+
+    assert_eq!(removed.query, "WHERE name = $p0");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_try_construct_errors() -> anyhow::Result<()> {
+    let err = Select::query("").try_construct().unwrap_err();
+    assert_eq!(err, QueryBuildError::EmptyTableName);
+
+    let err = Select::query("test_data")
+        .add_condition("bad\0name", None, "x")
+        .try_construct()
+        .unwrap_err();
+    assert!(matches!(err, QueryBuildError::InvalidIdentifier(_)));
+
+    let err = Select::query("test_data")
+        .add_field("count()", Some("total"))
+        .try_construct()
+        .unwrap_err();
+    assert_eq!(err, QueryBuildError::ExpressionAsField("count()".into()));
+
+    let query = Select::query("test_data")
+        .add_field(FieldPath::raw("count()"), Some("total"))
+        .try_construct()?;
+    assert_eq!(query.query.trim(), "SELECT count() AS total FROM test_data");
+
+    let query = Select::query("test_data")
+        .add_condition("name; DELETE test_data", None, "x")
+        .try_construct()?;
+    assert_eq!(
+        query.query,
+        "SELECT * FROM test_data WHERE `name; DELETE test_data` = $p0"
+    );
+
+    let err = Select::query("test_data")
+        .add_condition("age", Some("= 1 OR"), 18)
+        .try_construct()
+        .unwrap_err();
+    assert!(matches!(err, QueryBuildError::InvalidOperator(_)));
+
+    for operator in ["--", "//", "#", "-- ", "/*"] {
+        let err = Select::query("test_data")
+            .add_condition("age", Some(operator), 18)
+            .try_construct()
+            .unwrap_err();
+        assert!(matches!(err, QueryBuildError::InvalidOperator(_)));
+    }
+
+    let query = Select::query("test_data")
+        .add_condition("score", Some("@1@"), "rust")
+        .add_condition("tags", Some("?="), "admin")
+        .try_construct()?;
+    assert_eq!(
+        query.query,
+        "SELECT * FROM test_data WHERE score @1@ $p0 AND tags ?= $p1"
+    );
+
+    let db = Arc::new(db().await?);
+    let res: Result<Vec<TestData>, _> = Upsert::query("")
+        .add_field_to_content("name", "Alice")
+        .run(&db, 0)
+        .await;
+
+    assert!(matches!(
+        res,
+        Err(crate::error::Error::DatabaseError(
+            DatabaseError::QueryBuildError(QueryBuildError::EmptyTableName)
+        ))
+    ));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_run_update_after_content() -> anyhow::Result<()> {
+    let db = Arc::new(db().await?);
+
+    let _: Vec<TestData> = TestData::default().create_query().run(&db, 0).await?;
+
+    let res: Vec<TestData> = Update::query("test_data")
+        .add_condition("name", None, "John Doe")
+        .add_field_to_content("age", 19)
+        .run(&db, 0)
+        .await?;
+
+    assert_eq!(res.len(), 1);
+    assert_eq!(res[0].age, 19);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_run_escaped_identifiers() -> anyhow::Result<()> {
+    let db = Arc::new(db().await?);
+
+    let query = Create::query("test-data")
+        .add_field_to_content("first-name", "John")
+        .add_field_to_content("select", 1)
+        .try_construct()?;
+    assert_eq!(
+        query.query,
+        "CREATE `test-data` CONTENT { \"first-name\": $p0, select: $p1 }"
+    );
+
+    #[derive(Debug, serde::Deserialize)]
+    struct Named {
+        #[serde(alias = "first-name")]
+        name: String,
+    }
+
+    let _: Vec<Named> = Create::query("test-data")
+        .add_field_to_content("first-name", "John")
+        .run(&db, 0)
+        .await?;
+
+    let res: Vec<Named> = Select::query("test-data")
+        .add_field("first-name", Some("name"))
+        .add_condition("first-name", None, "John")
+        .run(&db, 0)
+        .await?;
+
+    assert_eq!(res.len(), 1);
+    assert_eq!(res[0].name, "John");
+
+    Ok(())
+}
+
+#[test]
+fn test_construct_add_fields() -> anyhow::Result<()> {
+    let query = Select::query("user")
+        .add_fields([("name", Some("username")), ("profile.age", None)])
+        .try_construct()?;
+
+    assert_eq!(
+        query.query.trim(),
+        "SELECT name AS username, profile.age FROM user"
+    );
+
+    let fields =
+        std::collections::BTreeMap::from([("first-name".to_string(), Some("name".to_string()))]);
+    let query = Select::query("user").add_fields(fields).try_construct()?;
+
+    assert_eq!(query.query.trim(), "SELECT `first-name` AS name FROM user");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_run_order_by() -> anyhow::Result<()> {
+    let query = Select::query("test_data")
+        .order_by(["age DESC"])
+        .add_order(OrderBy::asc("name").collate().numeric())
+        .try_construct()?;
+    assert_eq!(
+        query.query,
+        "SELECT * FROM test_data  ORDER BY age DESC, name COLLATE NUMERIC ASC"
+    );
+
+    let query = Select::query("test_data")
+        .order_by([OrderBy::rand()])
+        .try_construct()?;
+    assert_eq!(query.query, "SELECT * FROM test_data  ORDER BY RAND()");
+
+    let db = Arc::new(db().await?);
+
+    for (name, age) in [("Alice", 30), ("Bob", 40), ("Carol", 20)] {
+        let _: Vec<TestData> = TestData::new(name.to_string(), age)
+            .create_query()
+            .run(&db, 0)
+            .await?;
+    }
+
+    let res: Vec<TestData> = Select::query("test_data")
+        .order_by([OrderBy::desc("age")])
+        .run(&db, 0)
+        .await?;
+
+    let names = res
+        .iter()
+        .map(|data| data.name.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(names, ["Bob", "Alice", "Carol"]);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_run_paginate() -> anyhow::Result<()> {
+    use futures::TryStreamExt;
+
+    let db = Arc::new(db().await?);
+
+    for (name, age) in [
+        ("Alice", 30),
+        ("Bob", 40),
+        ("Carol", 20),
+        ("Dave", 30),
+        ("Eve", 30),
+        ("Frank", 50),
+        ("Grace", 10),
+    ] {
+        let _: Vec<TestData> = TestData::new(name.to_string(), age)
+            .create_query()
+            .run(&db, 0)
+            .await?;
+    }
+
+    let query = Select::query("test_data")
+        .add_condition("age", Some(">"), 10)
+        .order_by([OrderBy::desc("age")]);
+
+    let pages: Vec<Vec<TestData>> = query.clone().paginate(2).pages(&db).try_collect().await?;
+    assert_eq!(pages.iter().map(Vec::len).collect::<Vec<_>>(), [2, 2, 2]);
+
+    let rows: Vec<TestData> = query.paginate(2).stream(&db).try_collect().await?;
+    let ages = rows.iter().map(|data| data.age).collect::<Vec<_>>();
+    assert_eq!(ages, [50, 40, 30, 30, 30, 20]);
+
+    let mut names = rows
+        .iter()
+        .map(|data| data.name.as_str())
+        .collect::<Vec<_>>();
+    names.sort();
+    assert_eq!(names, ["Alice", "Bob", "Carol", "Dave", "Eve", "Frank"]);
+
+    let res: Result<Vec<TestData>, _> = Select::query("test_data")
+        .order_by([OrderBy::rand()])
+        .paginate(2)
+        .stream(&db)
+        .try_collect()
+        .await;
+    assert!(matches!(
+        res,
+        Err(crate::error::Error::DatabaseError(
+            DatabaseError::QueryBuildError(QueryBuildError::InvalidPaginationOrder(_))
+        ))
+    ));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_run_transaction() -> anyhow::Result<()> {
+    let db = Arc::new(db().await?);
+
+    let mut res = Transaction::new()
+        .add_statement(TestData::new("Alice".to_string(), 30).create_query())
+        .add_statement(TestData::new("Bob".to_string(), 40).create_query())
+        .add_statement(
+            Update::query("test_data")
+                .add_condition("name", None, "Alice")
+                .add_field_to_content("age", 31),
+        )
+        .run(&db)
+        .await?;
+
+    let created: Vec<TestData> = res.take(1)?;
+    assert_eq!(created[0].name, "Bob");
+
+    let updated: Vec<TestData> = res.take(2)?;
+    assert_eq!(updated[0].age, 31);
+
+    let err = Transaction::new()
+        .add_statement(TestData::new("Carol".to_string(), 20).create_query())
+        .add_statement("CREATE test_data:dup")
+        .add_statement("CREATE test_data:dup")
+        .run(&db)
+        .await
+        .unwrap_err();
+
+    match err {
+        crate::error::Error::DatabaseError(DatabaseError::TransactionFailed {
+            index,
+            statement,
+            source,
+        }) => {
+            assert_eq!(index, 2);
+            assert_eq!(statement, "CREATE test_data:dup");
+            assert!(matches!(*source, DatabaseError::RecordExists { .. }));
+        }
+        err => panic!("unexpected error: {}", err),
+    }
+
+    let all: Vec<TestData> = Select::query("test_data").run(&db, 0).await?;
+    assert_eq!(all.len(), 2);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_run_batch() -> anyhow::Result<()> {
+    #[derive(Debug, serde::Deserialize)]
+    struct Count {
+        total: usize,
+    }
+
+    let db = Arc::new(db().await?);
+
+    for (name, age) in [("Alice", 30), ("Bob", 40), ("Carol", 20)] {
+        let _: Vec<TestData> = TestData::new(name.to_string(), age)
+            .create_query()
+            .run(&db, 0)
+            .await?;
+    }
+
+    let (adults, count): (Vec<TestData>, Option<Count>) = Batch::new()
+        .push(Select::query("test_data").add_condition("age", Some(">="), 30))
+        .push(
+            Select::query("test_data")
+                .add_field(FieldPath::raw("count()"), Some("total"))
+                .group_all(),
+        )
+        .run(&db)
+        .await?;
+
+    assert_eq!(adults.len(), 2);
+    assert_eq!(count.map(|count| count.total), Some(3));
+
+    let res: Result<(Vec<TestData>,), _> = Batch::new()
+        .push(Select::query("test_data"))
+        .push(Select::query("test_data"))
+        .run(&db)
+        .await;
+    assert!(matches!(
+        res,
+        Err(crate::error::Error::DatabaseError(
+            DatabaseError::BatchSizeMismatch {
+                expected: 1,
+                statements: 2
+            }
+        ))
+    ));
+
+    // Builders bind into the batch's bindings, after the values of earlier statements.
+    let mut limit = Bindings::default();
+    let placeholder = limit.bind(5);
+    let (_, created): (Option<i64>, Vec<TestData>) = Batch::new()
+        .push(BoundQuery::new(
+            format!("LET $limit = {}", placeholder),
+            limit,
+        ))
+        .push(
+            Create::query("test_data")
+                .add_field_to_content("name", "Dave")
+                .add_field_to_content("age", 1),
+        )
+        .run(&db)
+        .await?;
+    assert_eq!(created[0].age, 1);
+
+    // Raw statements must not use the placeholder names, which could shadow a bound value.
+    let err = Transaction::new()
+        .add_statement("LET $p0 = 5")
+        .add_statement(Create::query("test_data").add_field_to_content("age", 1))
+        .try_construct()
+        .unwrap_err();
+    assert_eq!(err, QueryBuildError::ReservedParameter("p0".to_string()));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_run_content_from() -> anyhow::Result<()> {
+    let db = Arc::new(db().await?);
+    let data = TestData::new("Alice".to_string(), 30);
+
+    let query = Create::query("test_data")
+        .set_content_from(&data)?
+        .try_construct()?;
+    assert_eq!(
+        query.query,
+        "CREATE test_data CONTENT { age: $p0, id: $p1, name: $p2 }"
+    );
+
+    let query = Create::query("test_data")
+        .set_content_from_with(&data, ContentOptions::new().skip_none())?
+        .try_construct()?;
+    assert_eq!(
+        query.query,
+        "CREATE test_data CONTENT { age: $p0, name: $p1 }"
+    );
+
+    let created: Vec<TestData> = Create::query("test_data")
+        .set_content_from_with(&data, ContentOptions::new().skip_none())?
+        .run(&db, 0)
+        .await?;
+
+    let mut changed = created[0].clone();
+    changed.age = 31;
+
+    let updated: Vec<TestData> = Update::query("test_data")
+        .add_condition("name", None, "Alice")
+        .set_content_from_with(&changed, ContentOptions::new().skip_id())?
+        .run(&db, 0)
+        .await?;
+
+    assert_eq!(updated.len(), 1);
+    assert_eq!(updated[0].age, 31);
+    assert_eq!(updated[0].id, created[0].id);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_run_update_modes() -> anyhow::Result<()> {
+    let query = Update::query("test_data")
+        .increment("age", 1)
+        .set("name", "Bob")
+        .unset("nickname")
+        .add_condition("name", None, "Alice")
+        .try_construct()?;
+    assert_eq!(
+        query.query,
+        "UPDATE test_data SET age += $p0, name = $p1, nickname = NONE WHERE name = $p2"
+    );
+
+    let query = Upsert::query("test_data")
+        .unset("nickname")
+        .try_construct()?;
+    assert_eq!(query.query, "UPSERT test_data UNSET nickname");
+
+    let err = Update::query("test_data")
+        .set("age", 1)
+        .patch(PatchOperation::remove("/name"))
+        .try_construct()
+        .unwrap_err();
+    assert_eq!(
+        err,
+        QueryBuildError::ConflictingData {
+            statement: "UPDATE"
+        }
+    );
+
+    let db = Arc::new(db().await?);
+
+    let _: Vec<TestData> = TestData::new("Alice".to_string(), 30)
+        .create_query()
+        .run(&db, 0)
+        .await?;
+
+    let res: Vec<TestData> = Update::query("test_data")
+        .decrement("age", 5)
+        .run(&db, 0)
+        .await?;
+    assert_eq!(res[0].age, 25);
+
+    let res: Vec<TestData> = Update::query("test_data")
+        .patch(PatchOperation::replace("/name", "Bob"))
+        .patch(PatchOperation::add("/age", 40))
+        .run(&db, 0)
+        .await?;
+    assert_eq!(res[0].name, "Bob");
+    assert_eq!(res[0].age, 40);
+
+    let res: Vec<TestData> = Update::query("test_data")
+        .replace(BTreeMap::from([
+            ("name".to_string(), Value::from("Carol")),
+            ("age".to_string(), Value::from(20)),
+        ]))
+        .run(&db, 0)
+        .await?;
+    assert_eq!(res[0].name, "Carol");
+    assert_eq!(res[0].age, 20);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_run_return() -> anyhow::Result<()> {
+    #[derive(Debug, serde::Deserialize)]
+    struct Name {
+        name: String,
+    }
+
+    let query = Delete::query("test_data")
+        .set_only(true)
+        .add_condition("name", None, "Alice")
+        .try_construct()?;
+    assert_eq!(
+        query.query,
+        "DELETE ONLY test_data WHERE name = $p0 RETURN BEFORE"
+    );
+
+    let db = Arc::new(db().await?);
+
+    TestData::new("Alice".to_string(), 30)
+        .create_query()
+        .set_return(Return::None)
+        .execute(&db)
+        .await?;
+
+    let names: Vec<Name> = TestData::new("Bob".to_string(), 40)
+        .create_query()
+        .set_return(Return::fields(["name"]))
+        .run(&db, 0)
+        .await?;
+    assert_eq!(names[0].name, "Bob");
+
+    let mut ages: Vec<i32> = Update::query("test_data")
+        .increment("age", 1)
+        .set_return(Return::value("age"))
+        .run(&db, 0)
+        .await?;
+    ages.sort();
+    assert_eq!(ages, [31, 41]);
+
+    let deleted: Vec<TestData> = Delete::query("test_data")
+        .add_condition("name", None, "Alice")
+        .set_return(Return::Before)
+        .run(&db, 0)
+        .await?;
+    assert_eq!(deleted.len(), 1);
+    assert_eq!(deleted[0].age, 31);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_run_select_targets() -> anyhow::Result<()> {
+    #[derive(Debug, serde::Deserialize)]
+    struct Post {
+        title: String,
+    }
+
+    #[derive(Debug, serde::Deserialize)]
+    struct Liked {
+        name: String,
+        liked: Vec<String>,
+    }
+
+    let db = Arc::new(db().await?);
+
+    let alice: Vec<TestData> = TestData::new("Alice".to_string(), 30)
+        .create_query()
+        .run(&db, 0)
+        .await?;
+    let alice = alice[0].id.clone().unwrap();
+
+    let _: Vec<TestData> = TestData::new("Bob".to_string(), 40)
+        .create_query()
+        .run(&db, 0)
+        .await?;
+
+    db.query("CREATE post:1 SET title = 'Post 1'; CREATE post:2 SET title = 'Post 2'; CREATE post:3 SET title = 'Post 3';")
+        .await?
+        .check()?;
+
+    Relate::query("likes")
+        .relate_items(
+            alice.clone(),
+            Thing::from(("post".to_string(), surrealdb::sql::Id::from(2))),
+        )
+        .execute(&db)
+        .await?;
+
+    let posts: Vec<Post> = Select::from(Target::graph(
+        alice.clone(),
+        GraphPath::new().out("likes").out("post"),
+    ))
+    .run(&db, 0)
+    .await?;
+    assert_eq!(posts.len(), 1);
+    assert_eq!(posts[0].title, "Post 2");
+
+    let query = Select::query("test_data")
+        .add_field("name", None)
+        .add_expression(
+            GraphPath::new().out("likes").out("post").field("title"),
+            Some("liked"),
+        )
+        .add_condition("age", Some("<"), 35);
+    assert_eq!(
+        query.clone().try_construct()?.query,
+        "SELECT name, ->likes->post.title AS liked FROM test_data WHERE age < $p0"
+    );
+
+    let liked: Vec<Liked> = query.run(&db, 0).await?;
+    assert_eq!(liked[0].name, "Alice");
+    assert_eq!(liked[0].liked, ["Post 2"]);
+
+    let posts: Vec<Post> = Select::from(Target::range("post", 2..)).run(&db, 0).await?;
+    assert_eq!(posts.len(), 2);
+
+    let subquery = Select::query("test_data").add_condition("age", Some(">"), 35);
+    let query = Select::from(subquery)
+        .add_field("name", None)
+        .add_condition("name", Some("!="), "Carol");
+    assert_eq!(
+        query.try_construct()?.query,
+        "SELECT name FROM (SELECT * FROM test_data WHERE age > $p0) WHERE name != $p1"
+    );
+
+    let older: Vec<Post> =
+        Select::from(Select::query("test_data").add_condition("age", Some(">"), 35))
+            .add_field("name", Some("title"))
+            .run(&db, 0)
+            .await?;
+    assert_eq!(older.len(), 1);
+    assert_eq!(older[0].title, "Bob");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_run_schema_definitions() -> anyhow::Result<()> {
+    use surrealdb::sql::index::Distance;
+    use surrealdb::sql::Kind;
+
+    #[derive(Debug, serde::Deserialize)]
+    struct User {
+        name: String,
+        age: i64,
+    }
+
+    let db = Arc::new(db().await?);
+
+    DefineNamespace::new("app")
+        .if_not_exists()
+        .execute(&db)
+        .await?;
+    DefineDatabase::new("shop")
+        .comment("it's a shop")
+        .execute(&db)
+        .await?;
+    DefineTable::new("user")
+        .schemafull()
+        .permissions(
+            Permissions::new()
+                .grant([Action::Select], Permission::Full)
+                .grant(
+                    [Action::Create, Action::Update, Action::Delete],
+                    Condition::is_not_none(FieldPath::raw("$auth.id")),
+                ),
+        )
+        .execute(&db)
+        .await?;
+    DefineTable::new("post").execute(&db).await?;
+    DefineTable::new("likes")
+        .relation(["user"], ["post"])
+        .changefeed(std::time::Duration::from_secs(3600))
+        .execute(&db)
+        .await?;
+
+    let transaction = Transaction::new()
+        .add_statement(DefineField::new("name", "user").kind(Kind::String))
+        .add_statement(
+            DefineField::new("age", "user")
+                .kind(Kind::Int)
+                .default(18)
+                .assert(Condition::compare(FieldPath::raw("$value"), ">=", 0)),
+        )
+        .add_statement(
+            DefineField::new("created", "user")
+                .kind(Kind::Option(Box::new(Kind::Datetime)))
+                .readonly(),
+        )
+        .add_statement(
+            DefineIndex::new("user_name", "user")
+                .fields(["name"])
+                .unique(),
+        )
+        .add_statement("DEFINE ANALYZER simple TOKENIZERS blank FILTERS lowercase")
+        .add_statement(
+            DefineIndex::new("post_body", "post")
+                .fields(["body"])
+                .kind(IndexKind::search("simple")),
+        )
+        .add_statement(
+            DefineIndex::new("post_embedding", "post")
+                .fields(["embedding"])
+                .mtree(3, Distance::Euclidean),
+        )
+        .add_statement(
+            DefineIndex::new("post_embedding_hnsw", "post")
+                .fields(["embedding"])
+                .kind(IndexKind::Hnsw {
+                    dimension: 3,
+                    distance: Distance::Cosine,
+                    ef_construction: Some(150),
+                    m: Some(12),
+                }),
+        )
+        .add_statement(
+            DefineEvent::new("user_created", "user")
+                .when(Condition::eq(FieldPath::raw("$event"), "CREATE"))
+                .then("CREATE log SET user = $after.id")
+                .then(Create::query("log").add_field_to_content("kind", "signup")),
+        );
+    transaction.run(&db).await?;
+
+    let users: Vec<User> = Create::query("user")
+        .add_field_to_content("name", "Alice")
+        .run(&db, 0)
+        .await?;
+    assert_eq!(users[0].name, "Alice");
+    assert_eq!(users[0].age, 18);
+
+    let logs: Option<usize> = db.query("RETURN count(SELECT * FROM log)").await?.take(0)?;
+    assert_eq!(logs, Some(2));
+
+    let result = Create::query("user")
+        .add_field_to_content("name", "Bob")
+        .add_field_to_content("age", -1)
+        .run::<_, Vec<User>>(&db, 0)
+        .await;
+    assert!(result.is_err());
+
+    let result = Create::query("user")
+        .add_field_to_content("name", "Alice")
+        .run::<_, Vec<User>>(&db, 0)
+        .await;
+    assert!(result.is_err(), "unique index should reject duplicates");
+
+    assert!(DefineTable::new("post").execute(&db).await.is_err());
+    DefineTable::new("post")
+        .overwrite()
+        .schemaless()
+        .execute(&db)
+        .await?;
+
+    Remove::event("user_created", "user").execute(&db).await?;
+    Remove::index("user_name", "user").execute(&db).await?;
+    Remove::field("age", "user").execute(&db).await?;
+    Remove::table("likes").execute(&db).await?;
+    Remove::table("likes").if_exists().execute(&db).await?;
+    assert!(Remove::table("likes").execute(&db).await.is_err());
+    Remove::database("shop").execute(&db).await?;
+    Remove::namespace("app").execute(&db).await?;
+
+    assert_eq!(
+        DefineEvent::new("empty", "user").try_construct(),
+        Err(QueryBuildError::MissingClause {
+            statement: "DEFINE EVENT",
+            clause: "THEN",
+        })
+    );
+    assert_eq!(
+        DefineIndex::new("empty", "user").try_construct(),
+        Err(QueryBuildError::MissingClause {
+            statement: "DEFINE INDEX",
+            clause: "FIELDS",
+        })
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_run_migrations() -> anyhow::Result<()> {
+    use surrealdb::sql::Kind;
+
+    let db = Arc::new(db().await?);
+
+    let migrator = Migrator::new()
+        .add_migration(Migration::from_surql(
+            2,
+            "add_age",
+            "DEFINE FIELD age ON TABLE user TYPE int DEFAULT 0;",
+        ))
+        .add_migration(
+            Migration::new(1, "create_users")
+                .statement(DefineTable::new("user").schemafull())
+                .statement(DefineField::new("name", "user").kind(Kind::String)),
+        )
+        .add_migration(Migration::embedded(
+            "migrations/0003_create_posts.surql",
+            "DEFINE TABLE post SCHEMALESS;\nCREATE post:1 SET title = 'Hello';",
+        )?);
+
+    let plan = migrator.plan_up(&db).await?;
+    assert_eq!(plan.versions(), [1, 2, 3]);
+    assert!(plan.to_string().starts_with(
+        "-- 1 create_users\nBEGIN TRANSACTION;\nDEFINE TABLE user SCHEMAFULL;\nDEFINE FIELD name ON TABLE user TYPE string;\nCREATE _migrations:1 CONTENT"
+    ));
+    assert!(migrator.applied(&db).await?.is_empty());
+
+    assert_eq!(migrator.migrate_to(&db, 2).await?, [1, 2]);
+
+    let status = migrator.status(&db).await?;
+    let states = status.iter().map(|s| s.state).collect::<Vec<_>>();
+    assert_eq!(
+        states,
+        [
+            MigrationState::Applied,
+            MigrationState::Applied,
+            MigrationState::Pending
+        ]
+    );
+
+    let applied = migrator.applied(&db).await?;
+    assert_eq!(applied[1].name, "add_age");
+    let add_age = Migration::from_surql(
+        2,
+        "add_age",
+        "DEFINE FIELD age ON TABLE user TYPE int DEFAULT 0;",
+    );
+    assert_eq!(applied[1].checksum, add_age.checksum()?);
+
+    #[derive(Debug, serde::Deserialize)]
+    struct User {
+        name: String,
+        age: i64,
+    }
+
+    let users: Vec<User> = Create::query("user")
+        .add_field_to_content("name", "Alice")
+        .run(&db, 0)
+        .await?;
+    assert_eq!(users[0].name, "Alice");
+    assert_eq!(users[0].age, 0);
+
+    assert!(matches!(
+        migrator.migrate_to(&db, 1).await,
+        Err(crate::error::Error::DatabaseError(
+            DatabaseError::MigrationError(MigrationError::CannotMigrateDown {
+                target: 1,
+                latest: 2
+            })
+        ))
+    ));
+    assert!(matches!(
+        migrator.migrate_to(&db, 9).await,
+        Err(crate::error::Error::DatabaseError(
+            DatabaseError::MigrationError(MigrationError::UnknownVersion(9))
+        ))
+    ));
+
+    assert_eq!(migrator.migrate_up(&db).await?, [3]);
+    assert!(migrator.migrate_up(&db).await?.is_empty());
+    migrator.verify(&db).await?;
+
+    let edited = Migrator::new()
+        .add_migration(
+            Migration::new(1, "create_users").statement(DefineTable::new("user").schemaless()),
+        )
+        .add_migration(Migration::from_surql(
+            2,
+            "add_age",
+            "DEFINE FIELD age ON TABLE user TYPE int DEFAULT 0;",
+        ));
+    assert!(matches!(
+        edited.migrate_up(&db).await,
+        Err(crate::error::Error::DatabaseError(
+            DatabaseError::MigrationError(MigrationError::ChecksumMismatch { version: 1, .. })
+        ))
+    ));
+    assert_eq!(
+        edited
+            .status(&db)
+            .await?
+            .iter()
+            .map(|s| s.state)
+            .collect::<Vec<_>>(),
+        [
+            MigrationState::Modified,
+            MigrationState::Applied,
+            MigrationState::Missing
+        ]
+    );
+
+    // Accepting the changed migration, and then the original one again.
+    assert_eq!(edited.repair(&db).await?, [1]);
+    assert!(edited.repair(&db).await?.is_empty());
+    assert_eq!(
+        edited
+            .status(&db)
+            .await?
+            .iter()
+            .map(|s| s.state)
+            .collect::<Vec<_>>(),
+        [
+            MigrationState::Applied,
+            MigrationState::Applied,
+            MigrationState::Missing
+        ]
+    );
+    assert_eq!(migrator.repair(&db).await?, [1]);
+    migrator.verify(&db).await?;
+
+    let failing = migrator.clone().add_migration(Migration::from_surql(
+        4,
+        "broken",
+        "DEFINE TABLE comment; CREATE user:bob SET name = 42;",
+    ));
+    assert!(matches!(
+        failing.migrate_up(&db).await,
+        Err(crate::error::Error::DatabaseError(
+            DatabaseError::MigrationError(MigrationError::Failed { version: 4, .. })
+        ))
+    ));
+    assert_eq!(failing.applied(&db).await?.len(), 3);
+
+    Ok(())
+}
+
+#[cfg(feature = "derive")]
+#[tokio::test]
+async fn test_run_derive_surreal_table() -> anyhow::Result<()> {
+    #[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize, SurrealTable)]
+    #[surreal(table = "person")]
+    struct Person {
+        id: Option<Thing>,
+        name: String,
+        #[serde(rename = "years")]
+        age: i32,
+        #[serde(skip)]
+        cached: Option<String>,
+    }
+
+    assert_eq!(Person::TABLE, "person");
+    assert_eq!(PersonFields::AGE.name(), "years");
+
+    // Stored under the names serde serializes the fields with.
+    #[derive(Clone, serde::Serialize, serde::Deserialize, SurrealTable)]
+    #[serde(rename_all = "camelCase")]
+    struct Profile {
+        display_name: String,
+        #[serde(rename(serialize = "mail", deserialize = "email"))]
+        email: String,
+        #[serde(skip_serializing, default)]
+        password: String,
+    }
+
+    let profile = Profile {
+        display_name: "Alice".to_string(),
+        email: "alice@example.com".to_string(),
+        password: "secret".to_string(),
+    };
+    assert_eq!(Profile::TABLE, "profile");
+    assert_eq!(ProfileFields::DISPLAY_NAME.name(), "displayName");
+    assert_eq!(ProfileFields::EMAIL.name(), "mail");
+    assert_eq!(
+        profile.content()?.into_keys().collect::<Vec<_>>(),
+        ["displayName", "mail"]
+    );
+
+    let db = Arc::new(db().await?);
+
+    let mut people = Vec::new();
+
+    for (name, age) in [("Alice", 30), ("Bob", 17)] {
+        let person = Person {
+            id: None,
+            name: name.to_string(),
+            age,
+            cached: Some("not stored".to_string()),
+        };
+
+        assert!(person.content()?.contains_key("years"));
+        assert!(!person.content()?.contains_key("cached"));
+        assert!(matches!(
+            person.update_query(),
+            Err(crate::error::Error::DatabaseError(
+                DatabaseError::QueryBuildError(QueryBuildError::MissingRecordId(_))
+            ))
+        ));
+
+        let created: Vec<Person> = person.create_query()?.run(&db, 0).await?;
+        people.extend(created);
+    }
+
+    let adults: Vec<Person> = Person::select()
+        .and_where(PersonFields::AGE.gte(18))
+        .order_by([PersonFields::NAME.desc()])
+        .run(&db, 0)
+        .await?;
+    assert_eq!(adults.len(), 1);
+    assert_eq!(adults[0].name, "Alice");
+    assert_eq!(adults[0].cached, None);
+
+    let mut alice = adults[0].clone();
+    alice.age = 31;
+    let updated: Vec<Person> = alice.update_query()?.run(&db, 0).await?;
+    assert_eq!(updated, [alice.clone()]);
+
+    // The record is targeted directly, instead of scanning the table for its id.
+    assert_eq!(
+        alice.delete_query()?.try_construct()?.query.trim(),
+        "DELETE $p0"
+    );
+    alice.delete_query()?.execute(&db).await?;
+
+    let remaining: Vec<Person> = Person::select().run(&db, 0).await?;
+    assert_eq!(remaining.len(), 1);
+    assert_eq!(remaining[0].name, "Bob");
+
+    let mut bob = remaining[0].clone();
+    bob.id = Some(Thing::from(("account", "bob")));
+    assert!(matches!(
+        bob.delete_query(),
+        Err(crate::error::Error::DatabaseError(
+            DatabaseError::QueryBuildError(QueryBuildError::InvalidRecordId { .. })
+        ))
+    ));
+
+    // An id that is not a record id is the id of a record of the table.
+    #[derive(Clone, Debug, serde::Serialize, serde::Deserialize, SurrealTable)]
+    #[surreal(table = "tag")]
+    struct Tag {
+        id: Option<String>,
+        label: String,
+    }
+
+    #[derive(Debug, serde::Deserialize)]
+    struct Label {
+        label: String,
+    }
+
+    db.query("CREATE tag:rust SET label = 'Rust'")
+        .await?
+        .check()?;
+
+    let tag = Tag {
+        id: Some("rust".to_string()),
+        label: "rust-lang".to_string(),
+    };
+    let updated: Vec<Label> = tag.update_query()?.run(&db, 0).await?;
+    assert_eq!(updated.len(), 1);
+    assert_eq!(updated[0].label, "rust-lang");
+
+    tag.delete_query()?.execute(&db).await?;
+    let tags: Vec<Label> = Select::query("tag").run(&db, 0).await?;
+    assert!(tags.is_empty());
+
+    Ok(())
+}
+
+#[cfg(feature = "derive")]
+#[tokio::test]
+async fn test_run_repository() -> anyhow::Result<()> {
+    use futures::TryStreamExt;
+
+    #[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize, SurrealTable)]
+    struct Account {
+        id: Option<Thing>,
+        name: String,
+        balance: i64,
+    }
+
+    #[derive(Clone, serde::Serialize)]
+    struct Balance {
+        balance: i64,
+    }
+
+    let db = Arc::new(db().await?);
+    let accounts = Repository::<Account, _>::new(db.clone());
+
+    let mut alice = accounts
+        .insert(&Account {
+            id: None,
+            name: "Alice".to_string(),
+            balance: 100,
+        })
+        .await?;
+    let alice_id = alice.id.clone().unwrap().id;
+
+    for (name, balance) in [("Bob", 5), ("Carol", 50), ("Dave", 0)] {
+        accounts
+            .insert(&Account {
+                id: None,
+                name: name.to_string(),
+                balance,
+            })
+            .await?;
+    }
+
+    assert_eq!(
+        accounts.find_by_id(alice_id.clone()).await?,
+        Some(alice.clone())
+    );
+    assert_eq!(accounts.find_by_id("missing").await?, None);
+    assert!(accounts.exists(alice_id.clone()).await?);
+    assert!(!accounts.exists("missing").await?);
+
+    assert_eq!(accounts.count(Filter::default()).await?, 4);
+    assert_eq!(accounts.count(AccountFields::BALANCE.gte(50)).await?, 2);
+    assert_eq!(accounts.count(AccountFields::BALANCE.gt(1000)).await?, 0);
+
+    let mut rich = accounts.find_many(AccountFields::BALANCE.gte(50)).await?;
+    rich.sort_by_key(|account| account.balance);
+    assert_eq!(rich.len(), 2);
+    assert_eq!(rich[1], alice);
+
+    alice.name = "Alice Smith".to_string();
+    assert_eq!(accounts.update(&alice).await?, Some(alice.clone()));
+
+    let merged = accounts
+        .merge(alice_id.clone(), &Balance { balance: 75 })
+        .await?
+        .unwrap();
+    assert_eq!(merged.name, "Alice Smith");
+    assert_eq!(merged.balance, 75);
+    assert_eq!(
+        accounts.merge("missing", &Balance { balance: 1 }).await?,
+        None
+    );
+
+    let pages = accounts
+        .paginate(Filter::default(), [AccountFields::BALANCE.desc()], 3)
+        .try_collect::<Vec<_>>()
+        .await?;
+    let balances = pages
+        .iter()
+        .map(|page| page.iter().map(|a| a.balance).collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+    assert_eq!(balances, [vec![75, 50, 5], vec![0]]);
+
+    assert_eq!(
+        accounts.delete(alice_id.clone()).await?.map(|a| a.name),
+        Some("Alice Smith".to_string())
+    );
+    assert_eq!(accounts.delete(alice_id).await?, None);
+    assert_eq!(accounts.count(Filter::default()).await?, 3);
+
+    // An entity with an id is created as that record.
+    let erin = Account {
+        id: Some(Thing::from(("account", "erin"))),
+        name: "Erin".to_string(),
+        balance: 10,
+    };
+    assert_eq!(accounts.insert(&erin).await?, erin);
+    assert!(accounts.insert(&erin).await.is_err());
+    assert_eq!(accounts.find_by_id("erin").await?, Some(erin));
+    accounts.delete("erin").await?;
+
+    // Any executor works, like a plain reference to the client.
+    let borrowed = Repository::<Account, _>::new(db.as_ref());
+    assert_eq!(borrowed.count(Filter::default()).await?, 3);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_run_executors() -> anyhow::Result<()> {
+    use once_cell::sync::Lazy;
+    use std::sync::{LazyLock, OnceLock};
+    use surrealdb::engine::local::{Db, Mem};
+    use surrealdb::Surreal;
+
+    static LAZY: Lazy<Surreal<Db>> = Lazy::new(Surreal::init);
+    static LAZY_LOCK: LazyLock<Surreal<Db>> = LazyLock::new(Surreal::init);
+    static ONCE: OnceLock<Surreal<Db>> = OnceLock::new();
+
+    struct TenantDb {
+        db: Surreal<Db>,
+        tenant: &'static str,
+    }
+
+    impl Executor for TenantDb {
+        type Connection = Db;
+
+        fn connection(&self) -> &Surreal<Db> {
+            &self.db
+        }
+
+        fn prepare(&self, mut query: BoundQuery) -> BoundQuery {
+            query.bindings.insert("tenant", self.tenant);
+            query
+        }
+    }
+
+    async fn count<E: Executor + ?Sized>(db: &E) -> anyhow::Result<usize> {
+        let data: Vec<TestData> = TestData::default().create_query().run(db, 0).await?;
+        assert_eq!(data.len(), 1);
+
+        let all: Vec<TestData> = Select::query("test_data").run(db, 0).await?;
+        Ok(all.len())
+    }
+
+    let plain = db().await?;
+    assert_eq!(count(&plain).await?, 1);
+    assert_eq!(count(&&plain).await?, 2);
+    assert_eq!(count(&Arc::new(plain.clone())).await?, 3);
+    assert_eq!(count(&Box::new(plain)).await?, 4);
+
+    LAZY.connect::<Mem>(()).await?;
+    LAZY.use_ns("test").use_db("test").await?;
+    assert_eq!(count(&LAZY).await?, 1);
+
+    LAZY_LOCK.connect::<Mem>(()).await?;
+    LAZY_LOCK.use_ns("test").use_db("test").await?;
+    assert_eq!(count(&LAZY_LOCK).await?, 1);
+
+    let _ = ONCE.set(db().await?);
+    assert_eq!(count(ONCE.get().unwrap()).await?, 1);
+
+    let tenant = TenantDb {
+        db: db().await?,
+        tenant: "acme",
+    };
+    Create::query("test_data")
+        .add_field_to_content("name", "Alice")
+        .add_field_to_content("age", 30)
+        .execute(&tenant)
+        .await?;
+
+    let (name, tenant): (Option<String>, Option<String>) = Batch::new()
+        .push("SELECT VALUE name FROM ONLY test_data LIMIT 1")
+        .push("RETURN $tenant")
+        .run(&tenant)
+        .await?;
+    assert_eq!(name.as_deref(), Some("Alice"));
+    assert_eq!(tenant.as_deref(), Some("acme"));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_run_live_query() -> anyhow::Result<()> {
+    use futures::StreamExt;
+    use std::time::Duration;
+    use tokio::time::timeout;
+
+    #[derive(serde::Deserialize)]
+    struct Change {
+        op: String,
+        path: String,
+    }
+
+    let db = db().await?;
+
+    let mut adults = Live::query("live_data")
+        .and_where(Condition::gte("age", 18))
+        .stream::<_, TestData>(&db)
+        .await?;
+    let mut changes = Live::query("live_data")
+        .set_diff(true)
+        .stream::<_, Vec<Change>>(&db)
+        .await?;
+
+    let alice: Option<TestData> = Create::query("live_data")
+        .add_field_to_content("name", "Alice")
+        .add_field_to_content("age", 30)
+        .set_only(true)
+        .run(&db, 0)
+        .await?;
+    let alice = alice
+        .and_then(|alice| alice.id)
+        .expect("created record has an id");
+
+    Create::query("live_data")
+        .add_field_to_content("name", "Bob")
+        .add_field_to_content("age", 12)
+        .execute(&db)
+        .await?;
+
+    Update::query("live_data")
+        .add_field_to_content("age", 31)
+        .set_content_type(ContentType::Merge)
+        .add_condition("id", None, alice.clone())
+        .execute(&db)
+        .await?;
+
+    Delete::query("live_data")
+        .add_condition("id", None, alice.clone())
+        .execute(&db)
+        .await?;
+
+    let mut notifications = Vec::new();
+    for _ in 0..3 {
+        let notification = timeout(Duration::from_secs(5), adults.next())
+            .await?
+            .expect("live query stream ended")?;
+        notifications.push((notification.action, notification.data.name));
+    }
+
+    assert_eq!(
+        notifications,
+        vec![
+            (LiveAction::Create, "Alice".to_string()),
+            (LiveAction::Update, "Alice".to_string()),
+            (LiveAction::Delete, "Alice".to_string()),
+        ]
+    );
+
+    let created = timeout(Duration::from_secs(5), changes.next())
+        .await?
+        .expect("live query stream ended")?;
+    assert_eq!(created.action, LiveAction::Create);
+    assert_eq!(created.data[0].op, "replace");
+    assert_eq!(created.data[0].path, "/");
+
+    drop(adults);
+    drop(changes);
+
+    #[derive(serde::Deserialize)]
+    struct TableInfo {
+        lives: BTreeMap<String, String>,
+    }
+
+    // The streams kill their live queries in the background.
+    let mut lives = usize::MAX;
+    for _ in 0..50 {
+        let info: Option<TableInfo> = db.query("INFO FOR TABLE live_data").await?.take(0)?;
+        lives = info.map_or(0, |info| info.lives.len());
+
+        if lives == 0 {
+            break;
+        }
+
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+    assert_eq!(lives, 0);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_run_error_classification() -> anyhow::Result<()> {
+    use surrealdb::error::{Api, Db};
+
+    fn database_error(err: crate::error::Error) -> DatabaseError {
+        match err {
+            crate::error::Error::DatabaseError(err) => err,
+        }
+    }
+
+    let db = db().await?;
+
+    DefineIndex::new("unique_name", "test_data")
+        .fields(["name"])
+        .unique()
+        .execute(&db)
+        .await?;
+    DefineField::new("age", "test_data")
+        .assert(Condition::gte(FieldPath::raw("$value"), 0))
+        .execute(&db)
+        .await?;
+
+    Batch::new()
+        .push("CREATE test_data:alice SET name = 'Alice', age = 30")
+        .run::<_, (Vec<TestData>,)>(&db)
+        .await?;
+
+    let err = Batch::new()
+        .push("CREATE test_data:alice SET name = 'Alice 2', age = 30")
+        .run::<_, (Vec<TestData>,)>(&db)
+        .await
+        .unwrap_err();
+    match database_error(err) {
+        DatabaseError::RecordExists { table, record } => {
+            assert_eq!(table, "test_data");
+            assert_eq!(record, "test_data:alice");
+        }
+        err => panic!("unexpected error: {}", err),
+    }
+
+    let err = Create::query("test_data")
+        .add_field_to_content("name", "Alice")
+        .add_field_to_content("age", 20)
+        .execute(&db)
+        .await
+        .unwrap_err();
+    match database_error(err) {
+        DatabaseError::UniqueViolation {
+            table,
+            record,
+            index,
+            ..
+        } => {
+            assert_eq!(table, "test_data");
+            assert_eq!(record, "test_data:alice");
+            assert_eq!(index, "unique_name");
+        }
+        err => panic!("unexpected error: {}", err),
+    }
+
+    let err = Create::query("test_data")
+        .add_field_to_content("name", "Bob")
+        .add_field_to_content("age", -1)
+        .execute(&db)
+        .await
+        .unwrap_err();
+    match database_error(err) {
+        DatabaseError::AssertionFailed {
+            table,
+            field,
+            value,
+            ..
+        } => {
+            assert_eq!(table, "test_data");
+            assert_eq!(field, "age");
+            assert_eq!(value, "-1");
+        }
+        err => panic!("unexpected error: {}", err),
+    }
+
+    #[derive(Debug, serde::Deserialize)]
+    struct WrongType {
+        #[allow(dead_code)]
+        name: i64,
+    }
+
+    let err = Select::query("test_data")
+        .run::<_, Vec<WrongType>>(&db, 0)
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        database_error(err),
+        DatabaseError::DeserializationError(_)
+    ));
+
+    // Remote databases only report the message.
+    let remote = |message: &str| {
+        DatabaseError::from(surrealdb::Error::from(Api::Query(message.to_string())))
+    };
+
+    assert!(matches!(
+        remote("You don't have permission to run this query on the `user` table"),
+        DatabaseError::PermissionDenied { table: Some(table), .. } if table == "user"
+    ));
+    assert!(matches!(
+        remote("The query was not executed because it exceeded the timeout"),
+        DatabaseError::Timeout
+    ));
+    assert!(matches!(
+        remote("There was a problem with a datastore transaction: Transaction write conflict"),
+        DatabaseError::TransactionConflict(_)
+    ));
+    assert!(matches!(
+        remote("There was a problem with a datastore transaction: Resource busy: "),
+        DatabaseError::TransactionConflict(_)
+    ));
+
+    // Embedded databases report the engine's error.
+    let embedded = |err: Db| DatabaseError::from(surrealdb::Error::from(err));
+
+    assert!(matches!(
+        embedded(Db::Tx("Transaction read conflict".to_string())),
+        DatabaseError::TransactionConflict(_)
+    ));
+    assert!(matches!(
+        embedded(Db::Tx("Transaction write conflict".to_string())),
+        DatabaseError::TransactionConflict(_)
+    ));
+    assert!(matches!(
+        embedded(Db::QueryNotExecutedDetail {
+            message: "There was a problem with a datastore transaction: Transaction read conflict"
+                .to_string()
+        }),
+        DatabaseError::TransactionConflict(_)
+    ));
+    // These fail the same way every time, retrying them does not help.
+    assert!(matches!(
+        embedded(Db::TxKeyAlreadyExists),
+        DatabaseError::ResponseError(_)
+    ));
+    assert!(matches!(
+        embedded(Db::TxConditionNotMet),
+        DatabaseError::ResponseError(_)
+    ));
+    assert!(matches!(
+        remote("An error occurred: merge conflict"),
+        DatabaseError::ResponseError(_)
+    ));
+    assert!(matches!(
+        remote("Found record: `conflict:1` which is not a relation, but expected a relation"),
+        DatabaseError::ResponseError(_)
+    ));
+    assert!(matches!(
+        remote("Database index `email` already contains 'a@b.c', with record `user:alice`"),
+        DatabaseError::UniqueViolation { index, record, .. } if index == "email" && record == "user:alice"
+    ));
+    assert!(matches!(
+        remote("Parse error: unexpected token"),
+        DatabaseError::ResponseError(_)
+    ));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_run_retry() -> anyhow::Result<()> {
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use std::time::Duration;
+    use surrealdb::engine::local::Db;
+    use surrealdb::Surreal;
+
+    /// Counts the attempts, as every attempt prepares the query again.
+    struct Counting {
+        db: Surreal<Db>,
+        attempts: AtomicU32,
+    }
+
+    impl Executor for Counting {
+        type Connection = Db;
+
+        fn connection(&self) -> &Surreal<Db> {
+            &self.db
+        }
+
+        fn prepare(&self, query: BoundQuery) -> BoundQuery {
+            self.attempts.fetch_add(1, Ordering::SeqCst);
+            query
+        }
+    }
+
+    let counting = Counting {
+        db: db().await?,
+        attempts: AtomicU32::new(0),
+    };
+    let attempts = || counting.attempts.swap(0, Ordering::SeqCst);
+
+    let policy = RetryPolicy::new(3)
+        .backoff(Duration::from_millis(1), Duration::from_millis(5))
+        .retry_on([ErrorKind::RecordExists]);
+
+    Create::query("test_data")
+        .set_only(true)
+        .add_field_to_content("name", "Carol")
+        .add_field_to_content("age", 20)
+        .execute(&counting)
+        .await?;
+    Batch::new()
+        .push("CREATE test_data:alice SET name = 'Alice', age = 30")
+        .run::<_, (Vec<TestData>,)>(&counting)
+        .await?;
+    attempts();
+
+    // Retried until the attempts run out.
+    let err = Create::query("test_data")
+        .add_field_to_content("id", Thing::from(("test_data", "alice")))
+        .add_field_to_content("name", "Alice")
+        .with_retry(policy.clone())
+        .execute(&counting)
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        crate::error::Error::DatabaseError(DatabaseError::RecordExists { .. })
+    ));
+    assert_eq!(attempts(), 3);
+
+    // A batch is not atomic, so it is never retried.
+    let err = Batch::new()
+        .push("CREATE test_data SET name = 'Bob', age = 40")
+        .push("CREATE test_data:alice SET name = 'Alice', age = 30")
+        .run::<_, (Vec<TestData>, Vec<TestData>)>(&Retrying::new(&counting, policy.clone()))
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        crate::error::Error::DatabaseError(DatabaseError::RecordExists { .. })
+    ));
+    assert_eq!(attempts(), 1);
+
+    // Errors of other kinds are not retried.
+    let err = Select::query("test_data")
+        .with_retry(policy.clone())
+        .run::<_, Vec<bool>>(&counting, 0)
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        crate::error::Error::DatabaseError(DatabaseError::DeserializationError(_))
+    ));
+    assert_eq!(attempts(), 1);
+
+    // A failed transaction is retried as a whole, by the connection's default policy.
+    let retrying = Retrying::new(&counting, policy);
+    let err = Transaction::new()
+        .add_statement("CREATE test_data SET name = 'Bob', age = 40")
+        .add_statement("CREATE test_data:alice SET name = 'Alice', age = 30")
+        .run(&retrying)
+        .await
+        .unwrap_err();
+    match err {
+        crate::error::Error::DatabaseError(err) => {
+            assert_eq!(err.kind(), ErrorKind::RecordExists)
+        }
+    }
+    assert_eq!(attempts(), 3);
+
+    // Carol, Alice and the Bob of the batch, but none of the transaction.
+    let all: Vec<TestData> = Select::query("test_data").run(&counting, 0).await?;
+    assert_eq!(all.len(), 3);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_run_retry_conflict() -> anyhow::Result<()> {
+    use super::runner::Runner;
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use std::time::Duration;
+    use surrealdb::dbs::Session;
+    use surrealdb::kvs::Datastore;
+
+    let database_error = |err| DatabaseError::from(surrealdb::Error::Db(err));
+
+    // The embedded connection runs one query at a time, so the conflicting transactions run on
+    // the datastore underneath it.
+    let path = std::env::temp_dir().join(format!("conflict-{}", surrealdb::Uuid::new_v4()));
+    let ds = Datastore::new(&format!("surrealkv://{}", path.display())).await?;
+    let session = Session::owner().with_ns("test").with_db("test");
+
+    ds.execute("CREATE counter:1 SET n = 0", &session, None)
+        .await?;
+
+    let increment = || async {
+        let mut responses = ds
+            .execute(
+                "BEGIN; \
+                 LET $n = (SELECT VALUE n FROM ONLY counter:1); \
+                 SLEEP 200ms; \
+                 UPDATE counter:1 SET n = $n + 1; \
+                 COMMIT;",
+                &session,
+                None,
+            )
+            .await
+            .map_err(database_error)?;
+
+        match responses.pop().map(|response| response.result) {
+            Some(Err(err)) => Err(database_error(err)),
+            _ => Ok(()),
+        }
+    };
+
+    let db = db().await?;
+    let attempts = AtomicU32::new(0);
+
+    // The first attempt loses against a write committed while it sleeps, the second one wins.
+    let attempt = || async {
+        if attempts.fetch_add(1, Ordering::SeqCst) > 0 {
+            return increment().await;
+        }
+
+        let competing = async {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            ds.execute("UPDATE counter:1 SET n += 1", &session, None)
+                .await
+        };
+        let (res, competing) = tokio::join!(increment(), competing);
+        competing
+            .map_err(database_error)?
+            .remove(0)
+            .result
+            .map_err(database_error)?;
+
+        let err = res.unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::TransactionConflict, "{}", err);
+
+        Err(err)
+    };
+
+    Runner::retry(&db, Some(&RetryPolicy::default()), attempt).await?;
+    assert_eq!(attempts.load(Ordering::SeqCst), 2);
+
+    let mut responses = ds
+        .execute("SELECT VALUE n FROM ONLY counter:1", &session, None)
+        .await?;
+    assert_eq!(responses.remove(0).result?, surrealdb::sql::Value::from(2));
+
+    std::fs::remove_dir_all(path)?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_run_observer() -> anyhow::Result<()> {
+    use std::sync::Mutex;
+    use std::time::Duration;
+
+    /// Records every call as `event operation table detail`.
+    #[derive(Default)]
+    struct Recorder {
+        events: Mutex<Vec<String>>,
+    }
+
+    impl Recorder {
+        fn record(&self, event: &str, query: &QueryInfo, detail: String) {
+            self.events.lock().unwrap().push(format!(
+                "{} {} {} {}",
+                event,
+                query.operation(),
+                query.table().unwrap_or("-"),
+                detail
+            ));
+        }
+
+        fn take(&self) -> Vec<String> {
+            std::mem::take(&mut self.events.lock().unwrap())
+        }
+    }
+
+    impl QueryObserver for Recorder {
+        fn before_execute(&self, query: &QueryInfo) {
+            self.record("before", query, query.text().unwrap_or("-").to_string());
+        }
+
+        fn after_execute(&self, query: &QueryInfo, _duration: Duration, rows: Option<u64>) {
+            self.record("after", query, format!("{:?}", rows));
+        }
+
+        fn on_error(&self, query: &QueryInfo, _duration: Duration, error: &DatabaseError) {
+            self.record("error", query, format!("{:?}", error.kind()));
+        }
+    }
+
+    let db = Observed::new(db().await?, Recorder::default());
+
+    Create::query("test_data")
+        .set_only(true)
+        .add_field_to_content("name", "Alice")
+        .add_field_to_content("age", 30)
+        .execute(&db)
+        .await?;
+    let all: Vec<TestData> = Select::query("test_data")
+        .and_where(Condition::gte("age", 18))
+        .run(&db, 0)
+        .await?;
+    assert_eq!(all.len(), 1);
+
+    assert_eq!(
+        db.observer().take(),
+        vec![
+            "before CREATE test_data CREATE ONLY test_data CONTENT { age: $p0, name: $p1 }"
+                .to_string(),
+            "after CREATE test_data Some(1)".to_string(),
+            "before SELECT test_data SELECT * FROM test_data WHERE age >= $p0".to_string(),
+            "after SELECT test_data Some(1)".to_string(),
+        ]
+    );
+
+    // Every attempt is reported, and failures with their kind.
+    let retrying = Retrying::new(
+        &db,
+        RetryPolicy::new(2)
+            .backoff(Duration::from_millis(1), Duration::from_millis(1))
+            .retry_on([ErrorKind::RecordExists]),
+    );
+    let id = all[0].id.clone().unwrap().to_string();
+    let err = Transaction::new()
+        .add_statement(format!("CREATE {} SET name = 'Alice', age = 30", id))
+        .run(&retrying)
+        .await;
+    assert!(err.is_err());
+
+    let events = db.observer().take();
+    assert_eq!(events.len(), 4);
+    assert!(events[0].starts_with("before TRANSACTION - BEGIN TRANSACTION;"));
+    assert_eq!(events[1], "error TRANSACTION - RecordExists");
+    assert_eq!(events[3], "error TRANSACTION - RecordExists");
+
+    Batch::new()
+        .push("SELECT * FROM test_data")
+        .push("SELECT * FROM test_data")
+        .run::<_, (Vec<TestData>, Vec<TestData>)>(&db)
+        .await?;
+    let events = db.observer().take();
+    assert!(events[0].starts_with("before BATCH -"));
+    assert_eq!(events[1], "after BATCH - Some(2)");
+
+    // Labelled by what was run, not by how many statements it has.
+    Batch::new()
+        .push("SELECT * FROM test_data")
+        .run::<_, (Vec<TestData>,)>(&db)
+        .await?;
+    let events = db.observer().take();
+    assert_eq!(events[1], "after BATCH - Some(1)");
+
+    // Definitions write their values into the text, so it is not reported, not even as part of
+    // a transaction.
+    DefineField::new("nickname", "test_data")
+        .default("secret")
+        .execute(&db)
+        .await?;
+    Transaction::new()
+        .add_statement(DefineTable::new("observed").comment("secret"))
+        .add_statement(Select::query("test_data"))
+        .run(&db)
+        .await?;
+    Remove::table("observed").execute(&db).await?;
+    assert_eq!(
+        db.observer().take(),
+        vec![
+            "before DEFINE FIELD - -".to_string(),
+            "after DEFINE FIELD - Some(0)".to_string(),
+            "before TRANSACTION - -".to_string(),
+            "after TRANSACTION - Some(1)".to_string(),
+            "before REMOVE TABLE - REMOVE TABLE observed".to_string(),
+            "after REMOVE TABLE - Some(0)".to_string(),
+        ]
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_run_metrics() -> anyhow::Result<()> {
+    use futures::TryStreamExt;
+    use std::time::Duration;
+
+    let metrics = Arc::new(QueryMetrics::with_buckets([
+        Duration::from_millis(1),
+        Duration::from_secs(60),
+    ]));
+    let db = Observed::new(db().await?, Arc::clone(&metrics));
+
+    for name in ["Alice", "Bob"] {
+        Create::query("test_data")
+            .set_only(true)
+            .add_field_to_content("name", name)
+            .add_field_to_content("age", 30)
+            .execute(&db)
+            .await?;
+    }
+    let all: Vec<TestData> = Select::query("test_data")
+        .paginate(10)
+        .stream(&db)
+        .try_collect()
+        .await?;
+    let id = all[0].id.clone().unwrap().to_string();
+    Batch::new()
+        .push(format!("CREATE {} SET name = 'Alice', age = 30", id))
+        .push("SELECT * FROM test_data")
+        .run::<_, (Vec<TestData>, Vec<TestData>)>(&db)
+        .await
+        .unwrap_err();
+
+    let snapshot = metrics.snapshot();
+    assert_eq!(snapshot.queries("CREATE", Some("test_data")), 2);
+    assert_eq!(snapshot.queries("SELECT", Some("test_data")), 1);
+    assert_eq!(snapshot.queries("BATCH", None), 1);
+    assert_eq!(snapshot.queries("SELECT", Some("other")), 0);
+    assert_eq!(snapshot.errors(ErrorKind::RecordExists), 1);
+    assert_eq!(snapshot.errors(ErrorKind::Timeout), 0);
+
+    assert_eq!(snapshot.stats("CREATE", Some("test_data")).unwrap().rows, 2);
+
+    let select = snapshot.stats("SELECT", Some("test_data")).unwrap();
+    assert_eq!(select.rows, 2);
+    assert_eq!(select.errors, 0);
+    assert_eq!(select.latency.count(), 1);
+    assert_eq!(
+        select.latency.buckets().last(),
+        Some((Duration::from_secs(60), 1))
+    );
+
+    let text = snapshot.to_prometheus();
+    info!("{}", text);
+    assert!(text.contains("surrealdb_queries_total{operation=\"CREATE\",table=\"test_data\"} 2\n"));
+    assert!(text.contains("surrealdb_query_failures_total{operation=\"BATCH\",table=\"\"} 1\n"));
+    assert!(text.contains("surrealdb_query_errors_total{kind=\"record_exists\"} 1\n"));
+    assert!(
+        text.contains("surrealdb_query_rows_total{operation=\"SELECT\",table=\"test_data\"} 2\n")
+    );
+    assert!(text.contains(
+        "surrealdb_query_duration_seconds_bucket{operation=\"SELECT\",table=\"test_data\",le=\"60\"} 1\n"
+    ));
+    assert!(text.contains(
+        "surrealdb_query_duration_seconds_count{operation=\"SELECT\",table=\"test_data\"} 1\n"
+    ));
+
+    metrics.reset();
+    assert_eq!(metrics.snapshot(), MetricsSnapshot::default());
+
+    // Reading a record is labelled with its table, a subquery with none.
+    let _: Vec<TestData> = Select::from(all[0].id.clone().unwrap()).run(&db, 0).await?;
+    let _: Vec<TestData> = Select::from(Select::query("test_data")).run(&db, 0).await?;
+
+    let snapshot = metrics.snapshot();
+    assert_eq!(snapshot.queries("SELECT", Some("test_data")), 1);
+    assert_eq!(snapshot.queries("SELECT", None), 1);
+    assert_eq!(snapshot.queries("SELECT", Some("")), 0);
+    assert_eq!(snapshot.stats("SELECT", Some("test_data")).unwrap().rows, 1);
+    assert_eq!(snapshot.stats("SELECT", None).unwrap().rows, 2);
 
     Ok(())
 }
@@ -0,0 +1,194 @@
+use crate::db::error::DatabaseError;
+use crate::db::observer::total_rows;
+use crate::db::runner::Runner;
+use crate::db::{
+    Batch, Bindings, BoundQuery, Executor, Query, QueryBuildError, QueryInfo, RetryPolicy,
+};
+use crate::{InternalResult, Result};
+use log::error;
+use surrealdb::Response;
+
+/// Runs several statements atomically in a single `BEGIN TRANSACTION; ...; COMMIT TRANSACTION;`
+/// request.
+///
+/// Each statement's values are bound alongside the others, so builders can be added as is. If
+/// any statement fails, none of them are applied and running the transaction returns
+/// [`DatabaseError::TransactionFailed`] naming the statement that failed.
+///
+/// # Examples
+///
+/// ```
+/// # use surrealdb_abstraction::db::{Create, Delete, Query, Statement, Transaction};
+///
+/// let transaction = Transaction::new()
+///     .add_statement(Create::query("user").add_field_to_content("name", "Alice"))
+///     .add_statement(Delete::query("session").add_condition("user", None, "alice"))
+///     .try_construct()?;
+///
+/// assert_eq!(
+///     transaction.query,
+///     "BEGIN TRANSACTION;\nCREATE user CONTENT { name: $p0 };\nDELETE session WHERE user = $p1;\nCOMMIT TRANSACTION;"
+/// );
+/// # Ok::<_, surrealdb_abstraction::db::QueryBuildError>(())
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct Transaction {
+    batch: Batch,
+    retry: Option<RetryPolicy>,
+}
+
+impl Transaction {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a statement, its results are at the next index of the [`TransactionResponse`].
+    ///
+    /// If the statement cannot be constructed, the transaction fails with that error when it is
+    /// constructed or run.
+    pub fn add_statement(mut self, query: impl Query) -> Self {
+        self.batch = self.batch.push(query);
+
+        self
+    }
+
+    pub fn len(&self) -> usize {
+        self.batch.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.batch.is_empty()
+    }
+
+    /// Retries the whole transaction with `policy` when it fails, instead of the executor's
+    /// default.
+    pub fn with_retry(mut self, policy: RetryPolicy) -> Self {
+        self.retry = Some(policy);
+
+        self
+    }
+
+    pub async fn run<E>(self, db: &E) -> Result<TransactionResponse>
+    where
+        E: Executor + ?Sized,
+    {
+        self.send(db).await.map_err(Into::into)
+    }
+
+    pub(crate) async fn send<E>(mut self, db: &E) -> InternalResult<TransactionResponse>
+    where
+        E: Executor + ?Sized,
+    {
+        let statements = self.batch.statements().to_vec();
+        let retry = self.retry.take();
+        let query = Runner::construct(self)?;
+        let info = QueryInfo::new(&query, None).with_operation("TRANSACTION");
+
+        Runner::retry(db, retry.as_ref(), || {
+            let query = query.clone();
+            let statements = statements.clone();
+
+            let attempt = async move { Self::check(Runner::send(db, query).await?, statements) };
+
+            Runner::observe(db, &info, attempt, |response| total_rows(&response.values))
+        })
+        .await
+    }
+
+    /// Fails with the statement that caused the transaction to fail, if any.
+    fn check(
+        mut response: Response,
+        statements: Vec<String>,
+    ) -> InternalResult<TransactionResponse> {
+        let mut errors = response.take_errors().into_iter().collect::<Vec<_>>();
+        errors.sort_by_key(|(index, _)| *index);
+
+        if !errors.is_empty() {
+            // Every statement of a failed transaction reports an error, the one that caused it
+            // is the first that was actually executed.
+            let failed = errors
+                .iter()
+                .position(|(_, err)| !is_not_executed(err))
+                .unwrap_or_default();
+
+            let (index, source) = errors.swap_remove(failed);
+            let statement = statements.get(index).cloned().unwrap_or_default();
+
+            error!(
+                "Transaction failed at statement {}: {} with error: {}",
+                index, statement, source
+            );
+
+            return Err(DatabaseError::TransactionFailed {
+                index,
+                statement,
+                source: Box::new(DatabaseError::from(source)),
+            });
+        }
+
+        Ok(TransactionResponse {
+            values: Runner::take_all(response)?,
+            statements,
+        })
+    }
+}
+
+impl Query for Transaction {
+    fn construct_with(
+        self,
+        bindings: &mut Bindings,
+    ) -> std::result::Result<String, QueryBuildError> {
+        bindings.merge(self.try_construct()?)
+    }
+
+    fn try_construct(self) -> std::result::Result<BoundQuery, QueryBuildError> {
+        let BoundQuery { query, bindings } = self.batch.try_construct()?;
+
+        Ok(BoundQuery::new(
+            format!("BEGIN TRANSACTION;\n{}\nCOMMIT TRANSACTION;", query),
+            bindings,
+        ))
+    }
+}
+
+fn is_not_executed(err: &surrealdb::Error) -> bool {
+    use surrealdb::error::Db;
+
+    matches!(
+        err,
+        surrealdb::Error::Db(Db::QueryNotExecuted | Db::QueryNotExecutedDetail { .. })
+    )
+}
+
+/// The results of a committed [`Transaction`], one per statement in the order they were added.
+#[derive(Debug)]
+pub struct TransactionResponse {
+    values: Vec<surrealdb::Value>,
+    statements: Vec<String>,
+}
+
+impl TransactionResponse {
+    /// Takes the result of the statement at `index`, like [`Response::take`] with a statement
+    /// index.
+    pub fn take<U>(&mut self, index: usize) -> Result<U>
+    where
+        U: serde::de::DeserializeOwned + 'static,
+    {
+        let value = self.values.get_mut(index).map(std::mem::take);
+
+        Runner::from_value(value.unwrap_or_default()).map_err(Into::into)
+    }
+
+    /// The constructed statements, in the order they were added.
+    pub fn statements(&self) -> &[String] {
+        &self.statements
+    }
+
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
@@ -1,5 +1,4 @@
 pub mod utils;
 
-pub use crate::utils::*;
 #[cfg(feature = "dotenv")]
 pub use dotenv::{dotenv, from_filename, from_path};